@@ -0,0 +1,212 @@
+//! Derive macros for [`bbse`](https://docs.rs/bbse): `#[derive(BbseEncode, BbseDecode)]`.
+//!
+//! Enable them through the `derive` feature of `bbse` rather than depending on
+//! this crate directly. Each field of the struct is written in declaration
+//! order with the crate's prefix-free stream codec:
+//!
+//! | Attribute                               | Field is coded with                    |
+//! |-----------------------------------------|----------------------------------------|
+//! | `#[bbse(range = a..b)]`                 | `BbseWriter::write(a, b, v)`           |
+//! | `#[bbse(range = a..=b)]`                | `BbseWriter::write_range(a..=b, v)`    |
+//! | `#[bbse(range = a..b, midpoint = m)]`   | `BbseWriter::write_from(a, b, v, m)`   |
+//! | none                                    | the field's own `BbseEncode` impl      |
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Data, DeriveInput, Expr, Field, Fields, Index, Member, Token};
+
+/// Derives `bbse::BbseEncode`, see the crate docs for the field attributes.
+#[proc_macro_derive(BbseEncode, attributes(bbse))]
+pub fn derive_encode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_encode(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `bbse::BbseDecode`, see the crate docs for the field attributes.
+#[proc_macro_derive(BbseDecode, attributes(bbse))]
+pub fn derive_decode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_decode(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// How one field is coded, from its `#[bbse(...)]` attributes.
+enum Coding {
+    Range {
+        start: Expr,
+        end: Expr,
+    },
+    Inclusive {
+        start: Expr,
+        last: Expr,
+    },
+    Midpoint {
+        start: Expr,
+        end: Expr,
+        midpoint: Expr,
+    },
+    Nested,
+}
+
+impl Coding {
+    fn of(field: &Field) -> syn::Result<Self> {
+        let mut range = None;
+        let mut midpoint = None;
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("bbse")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("range") {
+                    let input = meta.value()?;
+                    let start: Expr = input.parse()?;
+                    let inclusive = input.peek(Token![..=]);
+                    if inclusive {
+                        input.parse::<Token![..=]>()?;
+                    } else {
+                        input.parse::<Token![..]>()?;
+                    }
+                    let end: Expr = input.parse()?;
+                    range = Some((start, inclusive, end));
+                    Ok(())
+                } else if meta.path.is_ident("midpoint") {
+                    midpoint = Some(meta.value()?.parse::<Expr>()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `range` or `midpoint`"))
+                }
+            })?;
+        }
+
+        match (range, midpoint) {
+            (Some((start, false, end)), None) => Ok(Coding::Range { start, end }),
+            (Some((start, true, last)), None) => Ok(Coding::Inclusive { start, last }),
+            (Some((start, false, end)), Some(midpoint)) => Ok(Coding::Midpoint {
+                start,
+                end,
+                midpoint,
+            }),
+            (Some((_, true, _)), Some(midpoint)) => Err(syn::Error::new(
+                midpoint.span(),
+                "`midpoint` needs a half-open range `a..b`",
+            )),
+            (None, Some(midpoint)) => Err(syn::Error::new(
+                midpoint.span(),
+                "`midpoint` needs a `range`",
+            )),
+            (None, None) => Ok(Coding::Nested),
+        }
+    }
+
+    fn write(&self, value: TokenStream2) -> TokenStream2 {
+        match self {
+            Coding::Range { start, end } => quote!(writer.write(#start, #end, #value)?;),
+            Coding::Inclusive { start, last } => {
+                quote!(writer.write_range(#start..=#last, #value)?;)
+            }
+            Coding::Midpoint {
+                start,
+                end,
+                midpoint,
+            } => quote!(writer.write_from(#start, #end, #value, #midpoint)?;),
+            Coding::Nested => quote!(::bbse::BbseEncode::write(&#value, writer)?;),
+        }
+    }
+
+    fn read(&self, field: &Field) -> TokenStream2 {
+        match self {
+            Coding::Range { start, end } => quote!(reader.read(#start, #end)?),
+            Coding::Inclusive { start, last } => quote!(reader.read_range(#start..=#last)?),
+            Coding::Midpoint {
+                start,
+                end,
+                midpoint,
+            } => quote!(reader.read_from(#start, #end, #midpoint)?),
+            Coding::Nested => {
+                let ty = &field.ty;
+                quote!(<#ty as ::bbse::BbseDecode>::read(reader)?)
+            }
+        }
+    }
+}
+
+fn fields<'a>(input: &'a DeriveInput, derive: &str) -> syn::Result<&'a Fields> {
+    match &input.data {
+        Data::Struct(data) => Ok(&data.fields),
+        _ => Err(syn::Error::new(
+            input.ident.span(),
+            format!("{} can only be derived for structs", derive),
+        )),
+    }
+}
+
+fn member(index: usize, field: &Field) -> Member {
+    match &field.ident {
+        Some(ident) => Member::Named(ident.clone()),
+        None => Member::Unnamed(Index::from(index)),
+    }
+}
+
+fn expand_encode(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let writes = fields(input, "BbseEncode")?
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let member = member(i, field);
+            Ok(Coding::of(field)?.write(quote!(self.#member)))
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::bbse::BbseEncode for #name #ty_generics #where_clause {
+            fn write(
+                &self,
+                writer: &mut ::bbse::BbseWriter,
+            ) -> ::core::result::Result<(), ::bbse::BbseError> {
+                #(#writes)*
+                ::core::result::Result::Ok(())
+            }
+        }
+    })
+}
+
+fn expand_decode(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = fields(input, "BbseDecode")?;
+    let reads = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let read = Coding::of(field)?.read(field);
+            let var = format_ident!("field{}", i);
+            Ok((var, read))
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let vars = reads.iter().map(|(var, _)| var);
+    let lets = reads.iter().map(|(var, read)| quote!(let #var = #read;));
+    let build = match fields {
+        Fields::Named(named) => {
+            let names = named.named.iter().map(|f| &f.ident);
+            quote!(Self { #(#names: #vars),* })
+        }
+        Fields::Unnamed(_) => quote!(Self(#(#vars),*)),
+        Fields::Unit => quote!(Self),
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::bbse::BbseDecode for #name #ty_generics #where_clause {
+            fn read(
+                reader: &mut ::bbse::BbseReader<'_>,
+            ) -> ::core::result::Result<Self, ::bbse::BbseError> {
+                #(#lets)*
+                ::core::result::Result::Ok(#build)
+            }
+        }
+    })
+}
@@ -0,0 +1,113 @@
+use bbse::{BbseDecode, BbseEncode, BbseError, BbseReader, BbseWriter};
+
+#[derive(Debug, Clone, PartialEq, BbseEncode, BbseDecode)]
+struct Sample {
+    #[bbse(range = 0..3)]
+    channel: u8,
+    #[bbse(range = -64..64)]
+    delta: i8,
+    #[bbse(range = 0..1000, midpoint = 16)]
+    offset: u16,
+}
+
+#[derive(Debug, PartialEq, BbseEncode, BbseDecode)]
+struct Frame {
+    #[bbse(range = 0..=255)]
+    id: u8,
+    first: Sample,
+    second: Sample,
+}
+
+#[derive(Debug, PartialEq, BbseEncode, BbseDecode)]
+struct Pair(
+    #[bbse(range = 0..10)] u32,
+    #[bbse(range = i64::MIN..=i64::MAX)] i64,
+);
+
+#[derive(Debug, PartialEq, BbseEncode, BbseDecode)]
+struct Marker;
+
+const SAMPLE: Sample = Sample {
+    channel: 2,
+    delta: -5,
+    offset: 20,
+};
+
+#[test]
+fn test_same_bits_as_writer() {
+    let mut writer = BbseWriter::new();
+    writer.write(0u8, 3, 2).unwrap();
+    writer.write(-64i8, 64, -5).unwrap();
+    writer.write_from(0u16, 1000, 20, 16).unwrap();
+    assert_eq!(SAMPLE.to_path(), Ok(writer.into_path()));
+}
+
+#[test]
+fn test_roundtrip() {
+    let frame = Frame {
+        id: 255,
+        first: SAMPLE,
+        second: Sample {
+            channel: 0,
+            delta: 63,
+            offset: 999,
+        },
+    };
+    let path = frame.to_path().unwrap();
+    assert_eq!(Frame::from_path(&path), Ok(frame));
+
+    let pair = Pair(9, i64::MIN);
+    assert_eq!(Pair::from_path(&pair.to_path().unwrap()), Ok(pair));
+
+    assert!(Marker.to_path().unwrap().is_empty());
+    assert_eq!(Marker::from_path(&Default::default()), Ok(Marker));
+}
+
+#[test]
+fn test_stream() {
+    let samples: Vec<Sample> = (0..20)
+        .map(|i| Sample {
+            channel: i % 3,
+            delta: i as i8 - 10,
+            offset: i as u16 * 50,
+        })
+        .collect();
+    let mut writer = BbseWriter::new();
+    for sample in &samples {
+        sample.write(&mut writer).unwrap();
+    }
+    let path = writer.into_path();
+
+    let mut reader = BbseReader::new(&path);
+    for sample in &samples {
+        assert_eq!(Sample::read(&mut reader).as_ref(), Ok(sample));
+    }
+    assert!(reader.is_empty());
+}
+
+#[test]
+fn test_errors() {
+    let bad = Sample {
+        channel: 3,
+        ..SAMPLE
+    };
+    assert_eq!(
+        bad.to_path(),
+        Err(BbseError::TargetOutOfBounds {
+            target: 3u8.into(),
+            start: 0u8.into(),
+            end: 3u8.into(),
+        })
+    );
+
+    let mut path = SAMPLE.to_path().unwrap();
+    path.push(false);
+    assert!(matches!(
+        Sample::from_path(&path),
+        Err(BbseError::PathTooLong { .. })
+    ));
+    assert!(matches!(
+        Sample::from_path(&Default::default()),
+        Err(BbseError::UnexpectedEnd { position: 0 })
+    ));
+}
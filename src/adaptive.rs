@@ -0,0 +1,142 @@
+//! Midpoint that follows the values already coded.
+
+use crate::{BbseError, BbseInt, BbsePath};
+
+/// Adaptive coder: like [`encode_from`](crate::encode_from), with the midpoint learned from the data
+///
+/// The root midpoint is an exponential moving average of every value coded so
+/// far: after each value it moves `1 / 2^shift` of the way towards it, rounded
+/// towards the value. Below the root the search gallops away from the midpoint
+/// in doubling steps (`m ± 1`, `m ± 3`, `m ± 7`, ...) before splitting at the
+/// center, so a value `d` away from the midpoint costs about `2·log2(d)` bits.
+///
+/// The update only depends on the values themselves, so an encoder and a
+/// decoder fed the same sequence stay in step without sending the midpoint.
+/// Use one instance on each side.
+///
+/// ```rust
+/// use bbse::AdaptiveCoder;
+/// let readings = [500u16, 502, 501, 503, 502, 502, 502];
+///
+/// let mut encoder = AdaptiveCoder::new(0u16, 1024);
+/// let paths: Vec<_> = readings.iter().map(|&v| encoder.encode(v)).collect();
+///
+/// let mut decoder = AdaptiveCoder::new(0u16, 1024);
+/// let decoded: Vec<_> = paths.iter().map(|p| decoder.decode(p)).collect();
+/// assert_eq!(decoded, readings);
+/// assert_eq!(encoder.midpoint(), 502);
+/// assert!(paths[6].is_empty());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdaptiveCoder<T> {
+    start: T,
+    end: T,
+    /// Key of the running average; [`midpoint`](AdaptiveCoder::midpoint) clamps it into the range.
+    estimate: u128,
+    shift: u32,
+}
+
+impl<T: BbseInt> AdaptiveCoder<T> {
+    /// Coder for `[start, end)` that starts at the center and adapts with `shift = 2`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is empty. See [`try_new`](AdaptiveCoder::try_new).
+    pub fn new(start: T, end: T) -> Self {
+        Self::try_new(start, end).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of [`new`](AdaptiveCoder::new)
+    pub fn try_new(start: T, end: T) -> Result<Self, BbseError> {
+        crate::check_range(start, end)?;
+        let (lo, last) = crate::keys(start, end);
+        Ok(Self {
+            start,
+            end,
+            estimate: crate::search::center(lo, last),
+            shift: 2,
+        })
+    }
+
+    /// Sets how slowly the midpoint follows the data: each value moves it by `1 / 2^shift`.
+    ///
+    /// `0` jumps straight to the last value; larger shifts smooth out noise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shift` is 128 or more.
+    pub fn with_shift(mut self, shift: u32) -> Self {
+        assert!(shift < 128, "shift ({}) must be below 128", shift);
+        self.shift = shift;
+        self
+    }
+
+    /// The root midpoint the next value is coded with.
+    pub fn midpoint(&self) -> T {
+        let (lo, last) = crate::keys(self.start, self.end);
+        T::from_key(self.estimate.clamp(lo.saturating_add(1).min(last), last))
+    }
+
+    /// Encodes `value` and updates the midpoint.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` lies outside `[start, end)`.
+    /// See [`try_encode`](AdaptiveCoder::try_encode).
+    pub fn encode(&mut self, value: T) -> BbsePath {
+        self.try_encode(value).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of [`encode`](AdaptiveCoder::encode); the midpoint is only
+    /// updated on success.
+    pub fn try_encode(&mut self, value: T) -> Result<BbsePath, BbseError> {
+        let path = crate::try_encode_with(self.start, self.end, value, &self.strategy())?;
+        self.update(value);
+        Ok(path)
+    }
+
+    /// Decodes one path and updates the midpoint; the path is not validated.
+    pub fn decode(&mut self, path: &BbsePath) -> T {
+        let value = crate::decode_with(self.start, self.end, path, &self.strategy());
+        self.update(value);
+        value
+    }
+
+    /// Checked version of [`decode`](AdaptiveCoder::decode); the midpoint is only
+    /// updated on success.
+    pub fn try_decode(&mut self, path: &BbsePath) -> Result<T, BbseError> {
+        let value = crate::try_decode_with(self.start, self.end, path, &self.strategy())?;
+        self.update(value);
+        Ok(value)
+    }
+
+    /// Splits `[lo, hi]` at the midpoint, then gallops away from it while one
+    /// side of the search is still open.
+    fn strategy(&self) -> impl Fn(T, T, usize) -> T {
+        let (first, last) = crate::keys(self.start, self.end);
+        let m = self.midpoint().to_key();
+        move |lo: T, hi: T, _depth| {
+            let (lo, hi) = (lo.to_key(), hi.to_key());
+            let mid = if lo == first && hi == last {
+                m
+            } else if hi == last && lo >= m {
+                lo.saturating_add(lo - m).saturating_add(1)
+            } else if lo == first && hi < m {
+                (hi + 1).saturating_sub(m - hi)
+            } else {
+                crate::search::center(lo, hi)
+            };
+            T::from_key(mid.clamp(lo + 1, hi))
+        }
+    }
+
+    fn update(&mut self, value: T) {
+        let key = value.to_key();
+        let step = |d: u128| d.div_ceil(1 << self.shift);
+        if key > self.estimate {
+            self.estimate += step(key - self.estimate);
+        } else {
+            self.estimate -= step(self.estimate - key);
+        }
+    }
+}
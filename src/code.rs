@@ -0,0 +1,257 @@
+use core::fmt;
+use core::str::FromStr;
+
+use crate::search::{self, Search};
+use crate::{BbseError, BbseInt};
+
+/// A path stored inline: up to [`CAPACITY`](BbseCode::CAPACITY) decisions in a `u128`.
+///
+/// Unlike [`BbsePath`](crate::BbsePath) it never allocates, so it works without a
+/// global allocator. Every path [`encode_inline`] produces fits, since the search
+/// over a range of any primitive integer type takes at most 128 steps.
+///
+/// ```rust
+/// use bbse::{decode_inline, encode_inline};
+/// let code = encode_inline(0u8, 8, 5);
+/// assert_eq!((code.bits(), code.len()), (0b10, 2));
+/// assert_eq!(decode_inline(0u8, 8, code), 5);
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct BbseCode {
+    /// The decisions, first one in the most significant of the `len` low bits.
+    bits: u128,
+    len: u8,
+}
+
+impl BbseCode {
+    /// Maximum number of decisions a code can hold.
+    pub const CAPACITY: usize = 128;
+
+    /// Empty code, which decodes to the first midpoint.
+    pub const fn new() -> Self {
+        Self { bits: 0, len: 0 }
+    }
+
+    /// Builds a code from its `len` low bits, first decision most significant.
+    ///
+    /// Returns `None` if `len` exceeds the capacity or `bits` has higher bits set.
+    pub const fn from_bits(bits: u128, len: usize) -> Option<Self> {
+        if len > Self::CAPACITY || (len < Self::CAPACITY && bits >> len != 0) {
+            return None;
+        }
+        Some(Self {
+            bits,
+            len: len as u8,
+        })
+    }
+
+    /// The decisions as a number, first decision most significant.
+    pub const fn bits(&self) -> u128 {
+        self.bits
+    }
+
+    pub const fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Decision at `index`: `true` for right, `false` for left.
+    pub const fn get(&self, index: usize) -> Option<bool> {
+        if index < self.len() {
+            Some(self.bit(index))
+        } else {
+            None
+        }
+    }
+
+    /// Appends a decision, failing once the code is full.
+    pub fn push(&mut self, bit: bool) -> Result<(), BbseError> {
+        if self.len() == Self::CAPACITY {
+            return Err(BbseError::CapacityExceeded {
+                capacity: Self::CAPACITY,
+            });
+        }
+        *self = self.pushed(bit);
+        Ok(())
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = bool> + ExactSizeIterator + '_ {
+        (0..self.len()).map(|i| self.bit(i))
+    }
+
+    /// Decision at `index`, which must be below `len()`.
+    const fn bit(&self, index: usize) -> bool {
+        self.bits >> (self.len() - 1 - index) & 1 == 1
+    }
+
+    /// [`push`](BbseCode::push) by value, for use in `const fn`; the caller checks capacity.
+    pub(crate) const fn pushed(self, bit: bool) -> Self {
+        Self {
+            bits: self.bits << 1 | bit as u128,
+            len: self.len + 1,
+        }
+    }
+}
+
+impl fmt::Display for BbseCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for bit in self.iter() {
+            f.write_str(if bit { "1" } else { "0" })?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for BbseCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BbseCode(\"{}\")", self)
+    }
+}
+
+impl FromStr for BbseCode {
+    type Err = BbseError;
+
+    fn from_str(s: &str) -> Result<Self, BbseError> {
+        let mut code = Self::new();
+        for c in s.bytes() {
+            match c {
+                b'0' => code.push(false)?,
+                b'1' => code.push(true)?,
+                _ => {
+                    return Err(BbseError::InvalidFormat {
+                        reason: "a path may only contain '0' and '1'",
+                    })
+                }
+            }
+        }
+        Ok(code)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<BbseCode> for crate::BbsePath {
+    fn from(code: BbseCode) -> Self {
+        code.iter().collect()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl TryFrom<&crate::BbsePath> for BbseCode {
+    type Error = BbseError;
+
+    fn try_from(path: &crate::BbsePath) -> Result<Self, BbseError> {
+        let mut code = Self::new();
+        for bit in path.iter() {
+            code.push(bit)?;
+        }
+        Ok(code)
+    }
+}
+
+/// [`encode`](crate::encode) into an inline [`BbseCode`], without allocating
+///
+/// # Panics
+///
+/// Panics if the range is empty or `target` lies outside `[start, end)`.
+/// See [`try_encode_inline`] for the non-panicking variant.
+pub fn encode_inline<T: BbseInt>(start: T, end: T, target: T) -> BbseCode {
+    try_encode_inline(start, end, target).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Fallible version of [`encode_inline`]
+pub fn try_encode_inline<T: BbseInt>(start: T, end: T, target: T) -> Result<BbseCode, BbseError> {
+    crate::check_target(start, end, target)?;
+    let (lo, last) = crate::keys(start, end);
+
+    let search = Search::new(lo, last, search::center(lo, last));
+    Ok(search::encode(search, target.to_key()))
+}
+
+/// [`decode`](crate::decode) for an inline [`BbseCode`]
+///
+/// The code is not validated; see [`try_decode_inline`] for a checked decoder.
+///
+/// # Panics
+///
+/// Panics if the range is empty.
+pub fn decode_inline<T: BbseInt>(start: T, end: T, code: BbseCode) -> T {
+    crate::check_range(start, end).unwrap_or_else(|e| panic!("{}", e));
+    let (lo, last) = crate::keys(start, end);
+
+    let search = Search::new(lo, last, search::center(lo, last));
+    T::from_key(decode_search(search, code))
+}
+
+/// Checked version of [`decode_inline`], see [`try_decode`](crate::try_decode)
+pub fn try_decode_inline<T: BbseInt>(start: T, end: T, code: BbseCode) -> Result<T, BbseError> {
+    crate::check_range(start, end)?;
+    let (lo, last) = crate::keys(start, end);
+
+    let search = Search::new(lo, last, search::center(lo, last));
+    search::decode_checked(search, code.iter()).map(T::from_key)
+}
+
+/// [`encode_inline`] for `u128` values, usable in `const` context
+///
+/// Codes only depend on where `target` sits within the range, so this gives the
+/// same code as [`encode_inline`] for any unsigned type. For a signed range
+/// encode the offsets `target - start` over `0..end - start` instead.
+///
+/// ```rust
+/// use bbse::{decode_const, encode_const, BbseCode};
+///
+/// const CODES: [BbseCode; 16] = {
+///     let mut codes = [BbseCode::new(); 16];
+///     let mut i = 0;
+///     while i < codes.len() {
+///         codes[i] = encode_const(0, 16, i as u128);
+///         i += 1;
+///     }
+///     codes
+/// };
+///
+/// assert_eq!(CODES[5].to_string(), "010");
+/// assert_eq!(decode_const(0, 16, CODES[5]), 5);
+/// ```
+///
+/// # Panics
+///
+/// Panics, or fails to compile in `const` context, if the range is empty or
+/// `target` lies outside `[start, end)`.
+pub const fn encode_const(start: u128, end: u128, target: u128) -> BbseCode {
+    assert!(start < end, "Invalid range: start >= end");
+    assert!(start <= target && target < end, "target out of bounds");
+    let last = end - 1;
+
+    search::encode(
+        Search::new(start, last, search::center(start, last)),
+        target,
+    )
+}
+
+/// [`decode_inline`] for `u128` values, usable in `const` context
+///
+/// The code is not validated, see [`encode_const`].
+///
+/// # Panics
+///
+/// Panics, or fails to compile in `const` context, if the range is empty.
+pub const fn decode_const(start: u128, end: u128, code: BbseCode) -> u128 {
+    assert!(start < end, "Invalid range: start >= end");
+    let last = end - 1;
+
+    decode_search(Search::new(start, last, search::center(start, last)), code)
+}
+
+/// Follows `code` without validating it, like `search::decode_lenient`.
+const fn decode_search(mut search: Search, code: BbseCode) -> u128 {
+    let mut i = 0;
+    while i < code.len() && !search.is_done() {
+        search = search.stepped(code.bit(i));
+        i += 1;
+    }
+    search.mid
+}
@@ -0,0 +1,60 @@
+//! Types that write themselves into a BBSE stream, field by field.
+
+use crate::{BbseError, BbsePath, BbseReader, BbseWriter};
+
+/// A value that can be appended to a [`BbseWriter`]
+///
+/// With the `derive` feature, `#[derive(BbseEncode)]` implements it for
+/// structs whose fields are integers with a `#[bbse(range = ...)]` attribute,
+/// optionally with `#[bbse(midpoint = ...)]`, or types that implement the trait
+/// themselves. Fields are written in declaration order.
+///
+/// ```rust
+/// # #[cfg(feature = "derive")] {
+/// use bbse::{BbseDecode, BbseEncode};
+///
+/// #[derive(Debug, PartialEq, BbseEncode, BbseDecode)]
+/// struct Sample {
+///     #[bbse(range = 0..3)]
+///     channel: u8,
+///     #[bbse(range = -64..64)]
+///     delta: i8,
+///     #[bbse(range = 0..1000, midpoint = 16)]
+///     offset: u16,
+/// }
+///
+/// let sample = Sample { channel: 2, delta: -5, offset: 20 };
+/// let bits = sample.to_path().unwrap();
+/// assert_eq!(Sample::from_path(&bits), Ok(sample));
+/// # }
+/// ```
+pub trait BbseEncode {
+    /// Appends every field to `writer`.
+    fn write(&self, writer: &mut BbseWriter) -> Result<(), BbseError>;
+
+    /// Codes the value as its own path.
+    fn to_path(&self) -> Result<BbsePath, BbseError> {
+        let mut writer = BbseWriter::new();
+        self.write(&mut writer)?;
+        Ok(writer.into_path())
+    }
+}
+
+/// A value that can be read back from a [`BbseReader`], see [`BbseEncode`]
+pub trait BbseDecode: Sized {
+    /// Reads every field from `reader`.
+    fn read(reader: &mut BbseReader<'_>) -> Result<Self, BbseError>;
+
+    /// Decodes a path from [`BbseEncode::to_path`]; it must hold exactly one value.
+    fn from_path(path: &BbsePath) -> Result<Self, BbseError> {
+        let mut reader = BbseReader::new(path);
+        let value = Self::read(&mut reader)?;
+        if !reader.is_empty() {
+            return Err(BbseError::PathTooLong {
+                len: path.len(),
+                max: reader.position(),
+            });
+        }
+        Ok(value)
+    }
+}
@@ -0,0 +1,163 @@
+use core::fmt;
+
+use crate::IntValue;
+
+/// Errors reported by the fallible (`try_*`) BBSE entry points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BbseError {
+    /// The range `[start, end)` contains no values.
+    EmptyRange { start: IntValue, end: IntValue },
+    /// The target does not lie within `[start, end)`.
+    ///
+    /// Reported by every API that takes a half-open range, including
+    /// [`PackedStack`](crate::PackedStack) and
+    /// [`IndexedSeq`](crate::IndexedSeq), which are built from one.
+    TargetOutOfBounds {
+        target: IntValue,
+        start: IntValue,
+        end: IntValue,
+    },
+    /// The target does not lie within the inclusive range `[first, last]`.
+    ///
+    /// Reported only where the range may end at the top of the type, so it
+    /// has no half-open `end`: [`encode_range`](crate::encode_range),
+    /// [`BbseWriter::write_range`](crate::BbseWriter::write_range), and
+    /// [`encode_unbounded`](crate::encode_unbounded) and the `write_unbounded`
+    /// stream methods for negative values.
+    TargetOutOfRange {
+        target: IntValue,
+        first: IntValue,
+        last: IntValue,
+    },
+    /// A custom midpoint does not lie strictly inside `(start, end)`.
+    InvalidMidpoint {
+        midpoint: IntValue,
+        start: IntValue,
+        end: IntValue,
+    },
+    /// A [`MidpointStrategy`](crate::MidpointStrategy) returned a midpoint
+    /// outside `(lo, hi]` for the values `[lo, hi]` left at `depth`.
+    InvalidSplit {
+        midpoint: IntValue,
+        lo: IntValue,
+        hi: IntValue,
+        depth: usize,
+    },
+    /// A histogram has `len` counts for a range of `expected` values.
+    HistogramMismatch { len: usize, expected: u128 },
+    /// The value at `position` is smaller than the one before it.
+    NotSorted { position: usize },
+    /// A [`RecordSchema`](crate::RecordSchema) has no field with the requested name.
+    UnknownField,
+    /// The field at `index` was declared with a different integer type.
+    FieldType { index: usize },
+    /// A [`RecordSchema`](crate::RecordSchema) already has a field called `name`.
+    DuplicateField { name: &'static str },
+    /// A pixel buffer has `len` bytes for an image that needs `expected`.
+    PixelBufferMismatch { len: usize, expected: usize },
+    /// The path continues after the search already reached a single value.
+    ///
+    /// `max` is the number of bits the search could consume along this path.
+    PathTooLong { len: usize, max: usize },
+    /// The bit at `position` leads to a value that was already compared
+    /// against earlier on the path, so no encoder produces this path.
+    UnreachableValue { position: usize },
+    /// A stream ended in the middle of the value starting at bit `position`.
+    UnexpectedEnd { position: usize },
+    /// A fixed-size path has no room for another decision.
+    CapacityExceeded { capacity: usize },
+    /// Serialized data is malformed.
+    InvalidFormat { reason: &'static str },
+    /// Serialized data uses a format version this crate does not know.
+    UnsupportedVersion { version: u8 },
+}
+
+impl fmt::Display for BbseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            BbseError::EmptyRange { start, end } => {
+                write!(f, "Invalid range: start ({}) >= end ({})", start, end)
+            }
+            BbseError::TargetOutOfBounds { target, start, end } => {
+                write!(f, "target ({}) out of bounds [{}, {})", target, start, end)
+            }
+            BbseError::TargetOutOfRange {
+                target,
+                first,
+                last,
+            } => write!(f, "target ({}) out of bounds [{}, {}]", target, first, last),
+            BbseError::InvalidMidpoint {
+                midpoint,
+                start,
+                end,
+            } => write!(
+                f,
+                "midpoint ({}) must be within (start={}, end={})",
+                midpoint, start, end
+            ),
+            BbseError::InvalidSplit {
+                midpoint,
+                lo,
+                hi,
+                depth,
+            } => write!(
+                f,
+                "split ({}) at depth {} must satisfy {} < split <= {}",
+                midpoint, depth, lo, hi
+            ),
+            BbseError::HistogramMismatch { len, expected } => write!(
+                f,
+                "histogram has {} counts but the range holds {} values",
+                len, expected
+            ),
+            BbseError::NotSorted { position } => {
+                write!(f, "value at {} is smaller than the one before it", position)
+            }
+            BbseError::UnknownField => write!(f, "no field with this name in the schema"),
+            BbseError::FieldType { index } => {
+                write!(f, "field {} holds a different integer type", index)
+            }
+            BbseError::DuplicateField { name } => {
+                write!(f, "field `{}` is already declared", name)
+            }
+            BbseError::PixelBufferMismatch { len, expected } => write!(
+                f,
+                "pixel buffer has {} bytes but the image needs {}",
+                len, expected
+            ),
+            BbseError::PathTooLong { len, max } => write!(
+                f,
+                "path of {} bits is too long: search terminates after {} bits",
+                len, max
+            ),
+            BbseError::UnreachableValue { position } => write!(
+                f,
+                "bit {} leads to a value already visited on this path",
+                position
+            ),
+            BbseError::UnexpectedEnd { position } => write!(
+                f,
+                "stream ends inside the value starting at bit {}",
+                position
+            ),
+            BbseError::CapacityExceeded { capacity } => {
+                write!(f, "path exceeds the capacity of {} bits", capacity)
+            }
+            BbseError::InvalidFormat { reason } => write!(f, "invalid BBSE data: {}", reason),
+            BbseError::UnsupportedVersion { version } => {
+                write!(f, "unsupported BBSE format version {}", version)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BbseError {}
+
+#[cfg(feature = "std")]
+impl From<BbseError> for std::io::Error {
+    fn from(e: BbseError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+    }
+}
@@ -0,0 +1,334 @@
+//! Versioned binary format for [`BBSEStack`].
+//!
+//! A stored stack carries everything needed to decode it again: the integer
+//! type, the range, the midpoint strategy and the number of values.
+//!
+//! | Size      | Field                                                                  |
+//! |-----------|------------------------------------------------------------------------|
+//! | 4         | Magic `b"BBSE"`                                                        |
+//! | 1         | Format version, currently `1`                                          |
+//! | 1         | Integer type: width in bytes (`1` to `16`), plus `0x80` if signed      |
+//! | 1         | Midpoint strategy: `0` = center, `1` = custom root midpoint            |
+//! | W         | `start`, big-endian two's complement, `W` = width / 8                  |
+//! | W         | `end` (exclusive), same encoding                                       |
+//! | W         | Root midpoint, only present for strategy `1`                           |
+//! | varint    | Number of paths `n` (unsigned LEB128)                                  |
+//! | n* varint | Length of each path in bits, bottom of the stack first                 |
+//! | ⌈Σ/8⌉     | All path bits back to back, most significant bit first, zero padded    |
+//!
+//! `usize` and `isize` are recorded with the width of the platform that wrote
+//! them. Every path is validated against the recorded range and midpoint on
+//! both [`to_bytes`](BBSEStack::to_bytes) and [`from_bytes`](BBSEStack::from_bytes).
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use bitvec::order::Msb0;
+use bitvec::slice::BitSlice;
+use bitvec::vec::BitVec;
+
+use crate::{try_decode, try_decode_from, BBSEStack, BbseError, BbseInt, BbsePath};
+
+const MAGIC: &[u8; 4] = b"BBSE";
+const VERSION: u8 = 1;
+
+const STRATEGY_CENTER: u8 = 0;
+const STRATEGY_ROOT: u8 = 1;
+
+/// How the first split of a stored stack was chosen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Midpoint<T> {
+    /// Paths from [`encode`](crate::encode): the search splits at the center.
+    Center,
+    /// Paths from [`encode_from`](crate::encode_from) with this root midpoint.
+    Root(T),
+}
+
+/// Range and midpoint a [`BBSEStack`] was encoded with, stored next to its paths.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StackLayout<T> {
+    pub start: T,
+    pub end: T,
+    pub midpoint: Midpoint<T>,
+}
+
+impl<T: BbseInt> StackLayout<T> {
+    pub fn new(start: T, end: T) -> Self {
+        Self {
+            start,
+            end,
+            midpoint: Midpoint::Center,
+        }
+    }
+
+    pub fn with_midpoint(start: T, end: T, midpoint: T) -> Self {
+        Self {
+            start,
+            end,
+            midpoint: Midpoint::Root(midpoint),
+        }
+    }
+
+    /// Checked decode of one path of a stack with this layout.
+    pub fn decode(&self, path: &BbsePath) -> Result<T, BbseError> {
+        match self.midpoint {
+            Midpoint::Center => try_decode(self.start, self.end, path),
+            Midpoint::Root(m) => try_decode_from(self.start, self.end, path, m),
+        }
+    }
+
+    /// Checked decode of every path in `stack`, bottom first.
+    pub fn decode_all(&self, stack: &BBSEStack) -> Result<Vec<T>, BbseError> {
+        stack.entries.iter().map(|p| self.decode(p)).collect()
+    }
+
+    fn check(&self) -> Result<(), BbseError> {
+        match self.midpoint {
+            Midpoint::Center => crate::check_range(self.start, self.end),
+            Midpoint::Root(m) => crate::check_midpoint(self.start, self.end, m),
+        }
+    }
+}
+
+impl BBSEStack {
+    /// Serializes the stack together with its layout, see the [`format`](crate::format) module.
+    pub fn to_bytes<T: BbseInt>(&self, layout: &StackLayout<T>) -> Result<Vec<u8>, BbseError> {
+        layout.check()?;
+        layout.decode_all(self)?;
+
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        out.push(type_tag::<T>());
+        match layout.midpoint {
+            Midpoint::Center => {
+                out.push(STRATEGY_CENTER);
+                put_value(&mut out, layout.start);
+                put_value(&mut out, layout.end);
+            }
+            Midpoint::Root(m) => {
+                out.push(STRATEGY_ROOT);
+                put_value(&mut out, layout.start);
+                put_value(&mut out, layout.end);
+                put_value(&mut out, m);
+            }
+        }
+
+        put_varint(&mut out, self.entries.len() as u64);
+        let mut bits = BitVec::<u8, Msb0>::new();
+        for path in &self.entries {
+            put_varint(&mut out, path.len() as u64);
+            bits.extend_from_bitslice(path.as_bitslice());
+        }
+        out.extend_from_slice(bits.as_raw_slice());
+        Ok(out)
+    }
+
+    /// Parses a stack written by [`to_bytes`](BBSEStack::to_bytes).
+    ///
+    /// `T` must match the integer type the stack was written with.
+    pub fn from_bytes<T: BbseInt>(bytes: &[u8]) -> Result<(BBSEStack, StackLayout<T>), BbseError> {
+        let mut source = bytes;
+        let parsed = read_stack(&mut source)?;
+        if !source.is_empty() {
+            return Err(BbseError::InvalidFormat {
+                reason: "trailing bytes",
+            });
+        }
+        Ok(parsed)
+    }
+
+    /// Writes [`to_bytes`](BBSEStack::to_bytes) to `writer`.
+    #[cfg(feature = "std")]
+    pub fn write_to<T: BbseInt, W: std::io::Write>(
+        &self,
+        layout: &StackLayout<T>,
+        mut writer: W,
+    ) -> std::io::Result<()> {
+        writer.write_all(&self.to_bytes(layout)?)
+    }
+
+    /// Reads one stack from `reader`, consuming exactly the bytes it occupies.
+    #[cfg(feature = "std")]
+    pub fn read_from<T: BbseInt, R: std::io::Read>(
+        reader: R,
+    ) -> std::io::Result<(BBSEStack, StackLayout<T>)> {
+        read_stack(&mut IoSource(reader))
+    }
+}
+
+/// Where [`read_stack`] pulls its bytes from.
+pub(crate) trait Source {
+    type Error: From<BbseError>;
+
+    fn fill(&mut self, buf: &mut [u8]) -> Result<(), Self::Error>;
+
+    /// Reads `n` bytes, allocating only as much as is actually available.
+    fn take(&mut self, n: usize) -> Result<Vec<u8>, Self::Error>;
+
+    fn byte(&mut self) -> Result<u8, Self::Error> {
+        let mut b = [0];
+        self.fill(&mut b)?;
+        Ok(b[0])
+    }
+}
+
+pub(crate) const TRUNCATED: BbseError = BbseError::InvalidFormat {
+    reason: "truncated data",
+};
+
+impl Source for &[u8] {
+    type Error = BbseError;
+
+    fn fill(&mut self, buf: &mut [u8]) -> Result<(), BbseError> {
+        let head = self.get(..buf.len()).ok_or(TRUNCATED)?;
+        buf.copy_from_slice(head);
+        *self = &self[buf.len()..];
+        Ok(())
+    }
+
+    fn take(&mut self, n: usize) -> Result<Vec<u8>, BbseError> {
+        let head = self.get(..n).ok_or(TRUNCATED)?;
+        *self = &self[n..];
+        Ok(head.to_vec())
+    }
+}
+
+#[cfg(feature = "std")]
+struct IoSource<R>(R);
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> Source for IoSource<R> {
+    type Error = std::io::Error;
+
+    fn fill(&mut self, buf: &mut [u8]) -> std::io::Result<()> {
+        self.0.read_exact(buf)
+    }
+
+    fn take(&mut self, n: usize) -> std::io::Result<Vec<u8>> {
+        use std::io::Read;
+
+        let mut buf = Vec::new();
+        (&mut self.0).take(n as u64).read_to_end(&mut buf)?;
+        if buf.len() < n {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        }
+        Ok(buf)
+    }
+}
+
+fn read_stack<T: BbseInt, S: Source>(
+    source: &mut S,
+) -> Result<(BBSEStack, StackLayout<T>), S::Error> {
+    let mut magic = [0; 4];
+    source.fill(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(BbseError::InvalidFormat {
+            reason: "missing BBSE magic",
+        }
+        .into());
+    }
+    let version = source.byte()?;
+    if version != VERSION {
+        return Err(BbseError::UnsupportedVersion { version }.into());
+    }
+    if source.byte()? != type_tag::<T>() {
+        return Err(BbseError::InvalidFormat {
+            reason: "integer type does not match",
+        }
+        .into());
+    }
+
+    let strategy = source.byte()?;
+    let start = get_value(source)?;
+    let end = get_value(source)?;
+    let layout = match strategy {
+        STRATEGY_CENTER => StackLayout::new(start, end),
+        STRATEGY_ROOT => StackLayout::with_midpoint(start, end, get_value(source)?),
+        _ => {
+            return Err(BbseError::InvalidFormat {
+                reason: "unknown midpoint strategy",
+            }
+            .into())
+        }
+    };
+    layout.check()?;
+
+    let count = get_varint(source)?;
+    let mut lengths = Vec::new();
+    let mut total = 0usize;
+    for _ in 0..count {
+        let len = usize::try_from(get_varint(source)?).map_err(|_| TOO_LARGE)?;
+        total = total.checked_add(len).ok_or(TOO_LARGE)?;
+        lengths.push(len);
+    }
+
+    let payload = source.take(total.div_ceil(8))?;
+    let bits = BitSlice::<u8, Msb0>::from_slice(&payload);
+    let mut stack = BBSEStack::new();
+    let mut pos = 0;
+    for len in lengths {
+        let path = BbsePath::from(&bits[pos..pos + len]);
+        layout.decode(&path)?;
+        stack.push(path);
+        pos += len;
+    }
+    Ok((stack, layout))
+}
+
+pub(crate) const TOO_LARGE: BbseError = BbseError::InvalidFormat {
+    reason: "length does not fit in memory",
+};
+
+/// Width in bytes, which stays below `0x80`, plus `0x80` for signed types.
+pub(crate) fn type_tag<T: BbseInt>() -> u8 {
+    (T::BITS / 8) as u8 | if T::SIGNED { 0x80 } else { 0 }
+}
+
+/// Bit pattern of `value` as the type's own two's complement.
+fn sign_flip<T: BbseInt>() -> u128 {
+    if T::SIGNED {
+        1 << (T::BITS - 1)
+    } else {
+        0
+    }
+}
+
+pub(crate) fn put_value<T: BbseInt>(out: &mut Vec<u8>, value: T) {
+    let raw = value.to_key() ^ sign_flip::<T>();
+    let width = T::BITS as usize / 8;
+    out.extend_from_slice(&raw.to_be_bytes()[16 - width..]);
+}
+
+pub(crate) fn get_value<T: BbseInt, S: Source>(source: &mut S) -> Result<T, S::Error> {
+    let mut raw = [0; 16];
+    let width = T::BITS as usize / 8;
+    source.fill(&mut raw[16 - width..])?;
+    Ok(T::from_key(u128::from_be_bytes(raw) ^ sign_flip::<T>()))
+}
+
+pub(crate) fn put_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+pub(crate) fn get_varint<S: Source>(source: &mut S) -> Result<u64, S::Error> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = source.byte()?;
+        let bits = u64::from(byte & 0x7f);
+        if bits << shift >> shift != bits {
+            break;
+        }
+        value |= bits << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(BbseError::InvalidFormat {
+        reason: "varint overflows 64 bits",
+    }
+    .into())
+}
@@ -0,0 +1,448 @@
+//! Lossless delta codec for RGB, RGBA and grayscale rasters.
+//!
+//! Every sample is predicted from its left and top neighbours with a
+//! [`Predictor`] (whichever neighbour exists at the edges; the first pixel is
+//! predicted as `0`), and only the residual `value - prediction` is stored. For
+//! samples of `d` bits residuals lie in `[-(2^d - 1), 2^d)`, so `[-255, 256)`
+//! for 8-bit channels. They are ranked by their distance from a tunable
+//! midpoint, `m, m + 1, m - 1, m + 2, ...`, and the rank is written with the
+//! self-delimiting search of [`encode_unbounded`](crate::encode_unbounded):
+//! a residual equal to the midpoint costs one bit, one `d` away about
+//! `2·log2(2d) + 1` bits.
+//!
+//! The residual range is not used as the search range itself: a prefix-free
+//! path over `[-255, 256)` always descends to a single value, so it would cost
+//! about 9 bits per residual wherever the midpoint sits.
+//!
+//! An encoded image is a small container:
+//!
+//! | Size      | Field                                                            |
+//! |-----------|------------------------------------------------------------------|
+//! | 4         | Magic `b"BBSI"`                                                  |
+//! | 1         | Format version, currently `1`                                    |
+//! | 1         | Colour type: `3` = RGB, `4` = RGBA, `0x80 + d` = `d`-bit gray    |
+//! | 1         | Predictor: `0` = left, `1` = up, `2` = average, `3` = Paeth      |
+//! | varint    | Width in pixels (unsigned LEB128)                                |
+//! | varint    | Height in pixels                                                 |
+//! | 4         | Midpoint, big-endian two's complement `i32`                      |
+//! | rest      | Residual codes in raster order, MSB first, zero padded           |
+//!
+//! ```rust
+//! use bbse::image::{ColorType, Image};
+//! // A 32 × 32 sky: the green channel brightens by one per row.
+//! let pixels: Vec<u8> = (0..32u8)
+//!     .flat_map(|y| (0..32).flat_map(move |_| [90, 140 + y, 230]))
+//!     .collect();
+//! let image = Image::new(32, 32, ColorType::Rgb8, pixels).unwrap();
+//!
+//! let bytes = image.encode();
+//! assert_eq!(bytes.len(), 651); // 3072 bytes raw
+//! assert_eq!(Image::decode(&bytes), Ok(image));
+//! ```
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use bitvec::order::Msb0;
+use bitvec::slice::BitSlice;
+use bitvec::vec::BitVec;
+
+use crate::format::{self, Source};
+use crate::search;
+use crate::BbseError;
+
+const MAGIC: &[u8; 4] = b"BBSI";
+const VERSION: u8 = 1;
+
+const GRAY: u8 = 0x80;
+
+/// Channel layout and sample size of an [`Image`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorType {
+    /// Red, green, blue; 8 bits each.
+    Rgb8,
+    /// Red, green, blue, alpha; 8 bits each.
+    Rgba8,
+    /// One channel of the given bit depth, `1` to `16`, such as a mask or a
+    /// depth map. Samples take one byte up to 8 bits and two big-endian bytes
+    /// above that.
+    Gray(u8),
+}
+
+impl ColorType {
+    /// Samples per pixel.
+    pub const fn channels(self) -> usize {
+        match self {
+            ColorType::Rgb8 => 3,
+            ColorType::Rgba8 => 4,
+            ColorType::Gray(_) => 1,
+        }
+    }
+
+    /// Bits per sample.
+    pub const fn bit_depth(self) -> u8 {
+        match self {
+            ColorType::Rgb8 | ColorType::Rgba8 => 8,
+            ColorType::Gray(depth) => depth,
+        }
+    }
+
+    /// Bytes each sample takes in the pixel buffer.
+    pub const fn bytes_per_sample(self) -> usize {
+        if self.bit_depth() > 8 {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// Largest sample value.
+    fn max(self) -> i32 {
+        (1 << self.bit_depth()) - 1
+    }
+
+    fn check(self) -> Result<(), BbseError> {
+        if !(1..=16).contains(&self.bit_depth()) {
+            return Err(BbseError::InvalidFormat {
+                reason: "bit depth must lie within 1..=16",
+            });
+        }
+        Ok(())
+    }
+
+    fn tag(self) -> u8 {
+        match self {
+            ColorType::Rgb8 | ColorType::Rgba8 => self.channels() as u8,
+            ColorType::Gray(depth) => GRAY | depth,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Result<Self, BbseError> {
+        let color = match tag {
+            3 => ColorType::Rgb8,
+            4 => ColorType::Rgba8,
+            _ if tag & GRAY != 0 => ColorType::Gray(tag & !GRAY),
+            _ => {
+                return Err(BbseError::InvalidFormat {
+                    reason: "unknown colour type",
+                })
+            }
+        };
+        color.check()?;
+        Ok(color)
+    }
+}
+
+/// How a sample is predicted from the samples of the same channel to its
+/// left (`a`), above (`b`) and above left (`c`).
+///
+/// In the first row and column every predictor uses whichever of `a` and `b`
+/// exists.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Predictor {
+    /// `a`, best for rows of similar values.
+    Left,
+    /// `b`, best for columns of similar values.
+    Up,
+    /// `(a + b) / 2`, rounded down.
+    #[default]
+    Average,
+    /// Whichever of `a`, `b` and `c` is closest to `a + b - c`, as in PNG.
+    Paeth,
+}
+
+impl Predictor {
+    fn tag(self) -> u8 {
+        self as u8
+    }
+
+    fn from_tag(tag: u8) -> Result<Self, BbseError> {
+        match tag {
+            0 => Ok(Predictor::Left),
+            1 => Ok(Predictor::Up),
+            2 => Ok(Predictor::Average),
+            3 => Ok(Predictor::Paeth),
+            _ => Err(BbseError::InvalidFormat {
+                reason: "unknown predictor",
+            }),
+        }
+    }
+
+    /// Prediction for sample `i` from the samples before it.
+    fn predict(self, samples: &[i32], i: usize, channels: usize, row: usize) -> i32 {
+        let left = (i % row >= channels).then(|| samples[i - channels]);
+        let up = (i >= row).then(|| samples[i - row]);
+        match (left, up) {
+            (Some(a), Some(b)) => match self {
+                Predictor::Left => a,
+                Predictor::Up => b,
+                Predictor::Average => (a + b) / 2,
+                Predictor::Paeth => paeth(a, b, samples[i - row - channels]),
+            },
+            (Some(p), None) | (None, Some(p)) => p,
+            (None, None) => 0,
+        }
+    }
+}
+
+fn paeth(a: i32, b: i32, c: i32) -> i32 {
+    let p = a + b - c;
+    let (pa, pb, pc) = ((p - a).abs(), (p - b).abs(), (p - c).abs());
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+/// A raw raster: rows top to bottom, pixels left to right, channels interleaved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: u32,
+    height: u32,
+    color: ColorType,
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    /// Wraps `pixels`, which must hold exactly `width · height` pixels of `color`
+    /// with every sample within its bit depth.
+    pub fn new(
+        width: u32,
+        height: u32,
+        color: ColorType,
+        pixels: Vec<u8>,
+    ) -> Result<Self, BbseError> {
+        color.check()?;
+        let expected = sample_count(width, height, color)?
+            .checked_mul(color.bytes_per_sample())
+            .ok_or(format::TOO_LARGE)?;
+        if pixels.len() != expected {
+            return Err(BbseError::PixelBufferMismatch {
+                len: pixels.len(),
+                expected,
+            });
+        }
+        let image = Self {
+            width,
+            height,
+            color,
+            pixels,
+        };
+        if let Some(&sample) = image.samples().iter().find(|&&v| v > color.max()) {
+            return Err(BbseError::TargetOutOfBounds {
+                target: sample.into(),
+                start: 0.into(),
+                end: (color.max() + 1).into(),
+            });
+        }
+        Ok(image)
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn color(&self) -> ColorType {
+        self.color
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    pub fn into_pixels(self) -> Vec<u8> {
+        self.pixels
+    }
+
+    /// Encodes the image with the [`Average`](Predictor::Average) predictor
+    /// and the residual midpoint at `0`.
+    pub fn encode(&self) -> Vec<u8> {
+        self.encode_with(Predictor::Average, 0)
+            .expect("0 is a valid midpoint")
+    }
+
+    /// Encodes the image with the [`Average`](Predictor::Average) predictor,
+    /// ranking residuals by their distance from `midpoint`.
+    ///
+    /// The midpoint must lie strictly inside the residual range, within
+    /// `(-255, 256)` for 8-bit samples.
+    pub fn encode_with_midpoint(&self, midpoint: i32) -> Result<Vec<u8>, BbseError> {
+        self.encode_with(Predictor::Average, midpoint)
+    }
+
+    /// Encodes the image with `predictor`, ranking residuals by their distance
+    /// from `midpoint`, see [`encode_with_midpoint`](Image::encode_with_midpoint).
+    pub fn encode_with(&self, predictor: Predictor, midpoint: i32) -> Result<Vec<u8>, BbseError> {
+        let residuals = Residuals::new(self.color, midpoint)?;
+
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        out.push(self.color.tag());
+        out.push(predictor.tag());
+        format::put_varint(&mut out, self.width.into());
+        format::put_varint(&mut out, self.height.into());
+        format::put_value(&mut out, midpoint);
+
+        let samples = self.samples();
+        let (channels, row) = self.layout();
+        let mut bits = BitVec::<u8, Msb0>::new();
+        for (i, &value) in samples.iter().enumerate() {
+            let residual = value - predictor.predict(&samples, i, channels, row);
+            search::encode_unbounded(residuals.rank(residual), residuals.max_rank(), |bit| {
+                bits.push(bit)
+            });
+        }
+        out.extend_from_slice(bits.as_raw_slice());
+        Ok(out)
+    }
+
+    /// Parses an image written by [`encode`](Image::encode) or its variants.
+    pub fn decode(bytes: &[u8]) -> Result<Self, BbseError> {
+        let mut source = bytes;
+        let mut magic = [0; 4];
+        source.fill(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(BbseError::InvalidFormat {
+                reason: "missing BBSI magic",
+            });
+        }
+        let version = source.byte()?;
+        if version != VERSION {
+            return Err(BbseError::UnsupportedVersion { version });
+        }
+        let color = ColorType::from_tag(source.byte()?)?;
+        let predictor = Predictor::from_tag(source.byte()?)?;
+        let width =
+            u32::try_from(format::get_varint(&mut source)?).map_err(|_| format::TOO_LARGE)?;
+        let height =
+            u32::try_from(format::get_varint(&mut source)?).map_err(|_| format::TOO_LARGE)?;
+        let residuals = Residuals::new(color, format::get_value(&mut source)?)?;
+        let len = sample_count(width, height, color)?;
+
+        let mut image = Self {
+            width,
+            height,
+            color,
+            pixels: Vec::new(),
+        };
+        let (channels, row) = image.layout();
+        let mut samples = Vec::new();
+        let mut bits = BitSlice::<u8, Msb0>::from_slice(source).iter().by_vals();
+        for i in 0..len {
+            let rank = search::decode_unbounded(residuals.max_rank(), || bits.next())
+                .ok_or(format::TRUNCATED)?;
+            let value = predictor.predict(&samples, i, channels, row) + residuals.unrank(rank);
+            if !(0..=color.max()).contains(&value) {
+                return Err(BbseError::InvalidFormat {
+                    reason: "pixel value out of range",
+                });
+            }
+            samples.push(value);
+        }
+        if bits.len() >= 8 {
+            return Err(BbseError::InvalidFormat {
+                reason: "trailing bytes",
+            });
+        }
+        if bits.any(|bit| bit) {
+            return Err(BbseError::InvalidFormat {
+                reason: "non-zero padding",
+            });
+        }
+
+        image.pixels = match color.bytes_per_sample() {
+            1 => samples.iter().map(|&v| v as u8).collect(),
+            _ => samples
+                .iter()
+                .flat_map(|&v| (v as u16).to_be_bytes())
+                .collect(),
+        };
+        Ok(image)
+    }
+
+    /// Every sample of the pixel buffer, in order.
+    fn samples(&self) -> Vec<i32> {
+        match self.color.bytes_per_sample() {
+            1 => self.pixels.iter().map(|&v| v.into()).collect(),
+            _ => self
+                .pixels
+                .chunks_exact(2)
+                .map(|b| u16::from_be_bytes([b[0], b[1]]).into())
+                .collect(),
+        }
+    }
+
+    /// Samples per pixel and per row.
+    fn layout(&self) -> (usize, usize) {
+        let channels = self.color.channels();
+        (channels, self.width as usize * channels)
+    }
+}
+
+/// Number of samples in a `width × height` image.
+fn sample_count(width: u32, height: u32, color: ColorType) -> Result<usize, BbseError> {
+    (width as usize)
+        .checked_mul(height as usize)
+        .and_then(|n| n.checked_mul(color.channels()))
+        .ok_or(format::TOO_LARGE)
+}
+
+/// Ranking of the residuals `[-max, max]` around a midpoint.
+struct Residuals {
+    midpoint: i32,
+    /// Residuals above and below the midpoint.
+    up: u128,
+    down: u128,
+}
+
+impl Residuals {
+    fn new(color: ColorType, midpoint: i32) -> Result<Self, BbseError> {
+        let max = color.max();
+        crate::check_midpoint(-max, max + 1, midpoint)?;
+        Ok(Self {
+            midpoint,
+            up: (max - midpoint) as u128,
+            down: (midpoint + max) as u128,
+        })
+    }
+
+    fn max_rank(&self) -> u128 {
+        self.up + self.down
+    }
+
+    /// Position of `residual` in `m, m + 1, m - 1, m + 2, ...`, skipping values
+    /// outside the residual range.
+    fn rank(&self, residual: i32) -> u128 {
+        let d = u128::from(residual.abs_diff(self.midpoint));
+        match residual.cmp(&self.midpoint) {
+            core::cmp::Ordering::Equal => 0,
+            core::cmp::Ordering::Greater if d <= self.down => 2 * d - 1,
+            core::cmp::Ordering::Greater => self.down + d,
+            core::cmp::Ordering::Less if d <= self.up => 2 * d,
+            core::cmp::Ordering::Less => self.up + d,
+        }
+    }
+
+    /// Inverse of [`rank`](Residuals::rank).
+    fn unrank(&self, rank: u128) -> i32 {
+        let d = if rank <= 2 * self.up.min(self.down) {
+            if rank % 2 == 1 {
+                (rank + 1) as i32 / 2
+            } else {
+                -(rank as i32 / 2)
+            }
+        } else if self.up > self.down {
+            (rank - self.down) as i32
+        } else {
+            -((rank - self.up) as i32)
+        };
+        self.midpoint + d
+    }
+}
@@ -0,0 +1,145 @@
+//! Packed sequence with sampled offsets for random access.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::packed::PackedPaths;
+use crate::{BbseError, BbseInt};
+
+/// Sequence of BBSE values in one bitstream, indexed every `rate` entries
+///
+/// Values are stored back to back as prefix-free stream paths, as in
+/// [`PackedStack`](crate::PackedStack). The bit offset of every `rate`-th value
+/// is kept aside, so [`get`](IndexedSeq::get) starts at the nearest sample
+/// before `i` and decodes at most `rate` values. A lower rate means faster
+/// access and one more offset per `rate` values; the default is 32.
+///
+/// ```rust
+/// use bbse::IndexedSeq;
+/// let mut seq = IndexedSeq::new(0u16, 1000).with_rate(4);
+/// for v in 0..100 {
+///     seq.push(v * 7);
+/// }
+/// assert_eq!(seq.get(42), Some(294));
+/// assert_eq!(seq.get(100), None);
+/// assert_eq!(seq.sample_count(), 25);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedSeq<T> {
+    paths: PackedPaths<T>,
+    /// Bit offset of values `0`, `rate`, `2·rate`, ...
+    samples: Vec<usize>,
+    rate: usize,
+    len: usize,
+}
+
+impl<T: BbseInt> IndexedSeq<T> {
+    /// Empty sequence for values in `[start, end)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is empty. See [`try_new`](IndexedSeq::try_new).
+    pub fn new(start: T, end: T) -> Self {
+        Self::try_new(start, end).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of [`new`](IndexedSeq::new)
+    pub fn try_new(start: T, end: T) -> Result<Self, BbseError> {
+        Ok(Self {
+            paths: PackedPaths::new(start, end)?,
+            samples: Vec::new(),
+            rate: 32,
+            len: 0,
+        })
+    }
+
+    /// Samples the offset of every `rate`-th value; values already pushed are resampled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rate` is zero.
+    pub fn with_rate(mut self, rate: usize) -> Self {
+        assert!(rate > 0, "sampling rate must be positive");
+        self.rate = rate;
+        self.samples.clear();
+        let mut pos = 0;
+        for i in 0..self.len {
+            if i % rate == 0 {
+                self.samples.push(pos);
+            }
+            pos = self.paths.decode_at(pos).1;
+        }
+        self
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `value` lies outside the sequence's range. See [`try_push`](IndexedSeq::try_push).
+    pub fn push(&mut self, value: T) {
+        self.try_push(value).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of [`push`](IndexedSeq::push)
+    pub fn try_push(&mut self, value: T) -> Result<(), BbseError> {
+        let pos = self.paths.push(value)?;
+        if self.len % self.rate == 0 {
+            self.samples.push(pos);
+        }
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Value at `index`, decoding at most [`rate`](IndexedSeq::rate) values.
+    pub fn get(&self, index: usize) -> Option<T> {
+        if index >= self.len {
+            return None;
+        }
+        let mut pos = self.samples[index / self.rate];
+        for _ in 0..index % self.rate {
+            pos = self.paths.decode_at(pos).1;
+        }
+        Some(self.paths.decode_at(pos).0)
+    }
+
+    /// Decodes every value, in order.
+    pub fn decode_all(&self) -> Vec<T> {
+        let mut values = Vec::with_capacity(self.len);
+        let mut pos = 0;
+        for _ in 0..self.len {
+            let (value, next) = self.paths.decode_at(pos);
+            values.push(value);
+            pos = next;
+        }
+        values
+    }
+
+    /// Number of values in the sequence.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Distance between sampled values.
+    pub fn rate(&self) -> usize {
+        self.rate
+    }
+
+    /// Number of sampled offsets kept.
+    pub fn sample_count(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Exact number of bits used by the stored paths, without the samples.
+    pub fn bit_len(&self) -> usize {
+        self.paths.bit_len()
+    }
+
+    pub fn clear(&mut self) {
+        self.paths.clear();
+        self.samples.clear();
+        self.len = 0;
+    }
+}
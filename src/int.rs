@@ -0,0 +1,130 @@
+use core::fmt;
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// Primitive integer types that BBSE can encode.
+///
+/// Implemented for `u8..=u128`, `usize`, `i8..=i128` and `isize`. The trait is
+/// sealed; the search itself runs on [`BbseInt::to_key`], an order-preserving
+/// mapping of the whole type onto `u128` that sends `MIN` to `0`. Because the
+/// mapping is a plain shift, a signed range like `[-128, 128)` splits exactly
+/// where the same-sized unsigned range `[0, 256)` would.
+pub trait BbseInt:
+    Copy + Ord + fmt::Debug + fmt::Display + Into<IntValue> + private::Sealed
+{
+    /// Smallest value of the type.
+    const MIN: Self;
+    /// Largest value of the type.
+    const MAX: Self;
+    /// Width of the type in bits.
+    const BITS: u32;
+    /// Whether the type is signed.
+    const SIGNED: bool;
+
+    /// Distance of `self` from `Self::MIN`.
+    fn to_key(self) -> u128;
+
+    /// Inverse of [`to_key`](BbseInt::to_key); `key` must not exceed `Self::MAX.to_key()`.
+    fn from_key(key: u128) -> Self;
+}
+
+macro_rules! impl_unsigned {
+    ($($t:ty),*) => {$(
+        impl private::Sealed for $t {}
+
+        impl BbseInt for $t {
+            const MIN: Self = <$t>::MIN;
+            const MAX: Self = <$t>::MAX;
+            const BITS: u32 = <$t>::BITS;
+            const SIGNED: bool = false;
+
+            fn to_key(self) -> u128 {
+                self as u128
+            }
+
+            fn from_key(key: u128) -> Self {
+                key as $t
+            }
+        }
+
+        impl From<$t> for IntValue {
+            fn from(value: $t) -> Self {
+                IntValue {
+                    negative: false,
+                    magnitude: value as u128,
+                }
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_signed {
+    ($($t:ty => $u:ty),*) => {$(
+        impl private::Sealed for $t {}
+
+        impl BbseInt for $t {
+            const MIN: Self = <$t>::MIN;
+            const MAX: Self = <$t>::MAX;
+            const BITS: u32 = <$t>::BITS;
+            const SIGNED: bool = true;
+
+            fn to_key(self) -> u128 {
+                (self as $u ^ <$t>::MIN as $u) as u128
+            }
+
+            fn from_key(key: u128) -> Self {
+                (key as $u ^ <$t>::MIN as $u) as $t
+            }
+        }
+
+        impl From<$t> for IntValue {
+            fn from(value: $t) -> Self {
+                IntValue {
+                    negative: value < 0,
+                    magnitude: value.unsigned_abs() as u128,
+                }
+            }
+        }
+    )*};
+}
+
+impl_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_signed!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128, isize => usize);
+
+/// An integer reported by [`BbseError`](crate::BbseError), whatever its original type.
+///
+/// Values compare equal when they are numerically equal, so `IntValue::from(5u8)`
+/// equals `IntValue::from(5i64)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IntValue {
+    negative: bool,
+    magnitude: u128,
+}
+
+impl IntValue {
+    /// The value as `i128`, if it fits.
+    pub fn to_i128(self) -> Option<i128> {
+        if self.negative {
+            0i128.checked_sub_unsigned(self.magnitude)
+        } else {
+            i128::try_from(self.magnitude).ok()
+        }
+    }
+
+    /// The value as `u128`, if it is not negative.
+    pub fn to_u128(self) -> Option<u128> {
+        (!self.negative).then_some(self.magnitude)
+    }
+}
+
+impl fmt::Display for IntValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.negative {
+            write!(f, "-{}", self.magnitude)
+        } else {
+            write!(f, "{}", self.magnitude)
+        }
+    }
+}
@@ -0,0 +1,264 @@
+//! Streaming BBSE values through `std::io`.
+//!
+//! The stream holds the same prefix-free paths as [`BbseWriter`](crate::BbseWriter),
+//! packed MSB first. The writer ends it with a `1` bit followed by zeros up to the
+//! next byte boundary, so the reader can tell padding from data without a
+//! length header.
+
+use std::io::{self, Read, Write};
+
+use crate::search::{self, Search};
+use crate::{BbseError, BbseInt};
+
+/// Bytes buffered before they are handed to the underlying writer or read from the reader.
+const CHUNK: usize = 4096;
+
+/// Writes values straight to an [`io::Write`], a few bytes at a time
+///
+/// Call [`finish`](BbseStreamWriter::finish) when done: it writes the last
+/// partial byte and the padding marker. Dropping the writer without it loses
+/// the buffered tail.
+///
+/// ```rust
+/// use bbse::{BbseStreamReader, BbseStreamWriter};
+/// let mut writer = BbseStreamWriter::new(Vec::new());
+/// for v in [3u16, 999, 0, 512] {
+///     writer.write_value(0, 1000, v).unwrap();
+/// }
+/// let bytes = writer.finish().unwrap();
+/// assert_eq!(bytes.len(), 5); // 39 bits of paths, then the padding marker
+///
+/// let mut reader = BbseStreamReader::new(&bytes[..]);
+/// for v in [3u16, 999, 0, 512] {
+///     assert_eq!(reader.read_value(0, 1000).unwrap(), v);
+/// }
+/// assert!(reader.is_at_end().unwrap());
+/// ```
+#[derive(Debug)]
+pub struct BbseStreamWriter<W: Write> {
+    inner: W,
+    buf: Vec<u8>,
+    /// Bits of the byte being filled, MSB first.
+    byte: u8,
+    used: u32,
+    bits: u64,
+}
+
+impl<W: Write> BbseStreamWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            buf: Vec::with_capacity(CHUNK),
+            byte: 0,
+            used: 0,
+            bits: 0,
+        }
+    }
+
+    /// Appends `value` from `[start, end)`.
+    ///
+    /// An invalid range or value is reported as [`io::ErrorKind::InvalidData`]
+    /// wrapping the [`BbseError`]; nothing is written then.
+    pub fn write_value<T: BbseInt>(&mut self, start: T, end: T, value: T) -> io::Result<()> {
+        crate::check_target(start, end, value)?;
+        let (lo, last) = crate::keys(start, end);
+        search::encode_full(
+            Search::new(lo, last, search::center(lo, last)),
+            value.to_key(),
+            |bit| self.push(bit),
+        );
+        self.spill()
+    }
+
+    /// Appends a non-negative `value` with no upper bound, see [`encode_unbounded`](crate::encode_unbounded).
+    pub fn write_unbounded<T: BbseInt>(&mut self, value: T) -> io::Result<()> {
+        let offset = crate::unbounded::offset(value)?;
+        search::encode_unbounded(offset, crate::unbounded::max_offset::<T>(), |bit| {
+            self.push(bit)
+        });
+        self.spill()
+    }
+
+    /// Number of path bits written so far, without padding.
+    pub fn bit_len(&self) -> u64 {
+        self.bits
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Writes all complete bytes and flushes the underlying writer.
+    ///
+    /// A partial last byte stays buffered until more bits or [`finish`](BbseStreamWriter::finish).
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.inner.write_all(&self.buf)?;
+        self.buf.clear();
+        self.inner.flush()
+    }
+
+    /// Pads the stream with a `1` and zeros to a whole byte, flushes it and
+    /// returns the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.push(true);
+        while self.used != 0 {
+            self.push(false);
+        }
+        self.flush()?;
+        Ok(self.inner)
+    }
+
+    fn push(&mut self, bit: bool) {
+        self.byte |= (bit as u8) << (7 - self.used);
+        self.used += 1;
+        self.bits += 1;
+        if self.used == 8 {
+            self.buf.push(self.byte);
+            self.byte = 0;
+            self.used = 0;
+        }
+    }
+
+    /// Hands the buffered bytes to the underlying writer once there are enough of them.
+    fn spill(&mut self) -> io::Result<()> {
+        if self.buf.len() >= CHUNK {
+            self.inner.write_all(&self.buf)?;
+            self.buf.clear();
+        }
+        Ok(())
+    }
+}
+
+/// Reads values written by [`BbseStreamWriter`] from an [`io::Read`]
+///
+/// Values must be read in the same order and with the same ranges they were
+/// written with. A stream that ends inside a value is reported as
+/// [`io::ErrorKind::UnexpectedEof`] wrapping [`BbseError::UnexpectedEnd`];
+/// after any error the reader's position is unspecified.
+#[derive(Debug)]
+pub struct BbseStreamReader<R: Read> {
+    inner: R,
+    buf: Vec<u8>,
+    /// Unread bytes are `buf[head..tail]`; `bit` bits of `buf[head]` are already used.
+    head: usize,
+    tail: usize,
+    bit: u32,
+    eof: bool,
+    pos: usize,
+}
+
+impl<R: Read> BbseStreamReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            buf: vec![0; CHUNK],
+            head: 0,
+            tail: 0,
+            bit: 0,
+            eof: false,
+            pos: 0,
+        }
+    }
+
+    /// Reads the next value from `[start, end)`.
+    pub fn read_value<T: BbseInt>(&mut self, start: T, end: T) -> io::Result<T> {
+        crate::check_range(start, end)?;
+        let (lo, last) = crate::keys(start, end);
+        self.pull(|next| search::decode_full(Search::new(lo, last, search::center(lo, last)), next))
+            .map(T::from_key)
+    }
+
+    /// Reads the next value written by [`BbseStreamWriter::write_unbounded`].
+    pub fn read_unbounded<T: BbseInt>(&mut self) -> io::Result<T> {
+        let max = crate::unbounded::max_offset::<T>();
+        self.pull(|next| search::decode_unbounded(max, next))
+            .map(crate::unbounded::from_offset)
+    }
+
+    /// Whether every bit before the padding marker has been read.
+    pub fn is_at_end(&mut self) -> io::Result<bool> {
+        Ok(self.limit()?.is_none())
+    }
+
+    /// Number of bits read so far.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Runs `decode` on the bits from the current position.
+    fn pull(
+        &mut self,
+        decode: impl FnOnce(&mut dyn FnMut() -> Option<bool>) -> Option<u128>,
+    ) -> io::Result<u128> {
+        let start = self.pos;
+        let mut failure = None;
+        let key = decode(&mut || match self.next_bit() {
+            Ok(bit) => bit,
+            Err(e) => {
+                failure = Some(e);
+                None
+            }
+        });
+        if let Some(e) = failure {
+            return Err(e);
+        }
+        key.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                BbseError::UnexpectedEnd { position: start },
+            )
+        })
+    }
+
+    fn next_bit(&mut self) -> io::Result<Option<bool>> {
+        let Some(limit) = self.limit()? else {
+            return Ok(None);
+        };
+        debug_assert!(self.bit < limit);
+        let bit = self.buf[self.head] >> (7 - self.bit) & 1 == 1;
+        self.bit += 1;
+        self.pos += 1;
+        if self.bit == 8 {
+            self.head += 1;
+            self.bit = 0;
+        }
+        Ok(Some(bit))
+    }
+
+    /// Number of data bits in the current byte, or `None` once they are all read.
+    ///
+    /// Keeps at least one byte of lookahead so the last byte, which holds the
+    /// padding marker, is recognised.
+    fn limit(&mut self) -> io::Result<Option<u32>> {
+        while self.tail - self.head < 2 && !self.eof {
+            self.buf.copy_within(self.head..self.tail, 0);
+            self.tail -= self.head;
+            self.head = 0;
+            match self.inner.read(&mut self.buf[self.tail..]) {
+                Ok(0) => self.eof = true,
+                Ok(n) => self.tail += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        // The marker byte is never consumed, so running out of bytes means
+        // the stream was empty or cut short.
+        let limit = if self.eof && self.tail - self.head <= 1 {
+            let last = self.buf[self.head..self.tail].first().copied().unwrap_or(0);
+            if last == 0 {
+                return Err(BbseError::InvalidFormat {
+                    reason: "missing padding marker",
+                }
+                .into());
+            }
+            7 - last.trailing_zeros()
+        } else {
+            8
+        };
+        Ok((self.bit < limit).then_some(limit))
+    }
+}
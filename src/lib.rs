@@ -3,17 +3,23 @@
 //! BBSE encodes integer values from a known sorted range as a compact path of binary decisions — the same steps a binary search would take to locate the value.
 //!
 //! This encoding is:
-//! - **Prefix-free** and minimal;
+//! - **Minimal** — the midpoint costs no bits at all;
 //! - **Deterministic** and reversible;
 //! - **Stack-compatible** — no headers, no length metadata;
 //! - **Range-aware** — optimized for values near the center.
 //!
 //! ## Overview
 //!
-//! Each value is represented as a `BitVec`, encoding the binary decisions taken while performing binary search to locate the value in `[start..end)`.
+//! Each value is represented as a [`BbsePath`], encoding the binary decisions taken while performing binary search to locate the value in `[start..end)`.
 //! The search terminates early if the midpoint equals the target value.
 //!
+//! Because of that early stop a path on its own is not self-delimiting. To pack
+//! many values into one buffer, use [`BbseWriter`] and [`BbseReader`], which
+//! write a prefix-free variant of the path that costs about the search depth.
+//!
 //! BBSE paths can be stored directly in a stack, enabling extremely compact storage.
+//! [`BBSEStack`] keeps one [`BbsePath`] per path; [`PackedStack`] keeps all of them in a
+//! single buffer with no per-value overhead.
 //!
 //! ## Features
 //!
@@ -25,13 +31,26 @@
 //! ## Examples
 //!
 //! ```rust
+//! # #[cfg(feature = "alloc")] {
 //! use bbse::{encode, decode};
 //! let path = encode(0, 256, 128); // one step or even empty path
 //! let value = decode(0, 256, &path);
 //! assert_eq!(value, 128);
+//! # }
 //! ```
 //!
+//! Any primitive integer type implementing [`BbseInt`] works, including signed ranges:
+//!
 //! ```rust
+//! # #[cfg(feature = "alloc")] {
+//! use bbse::{encode, decode};
+//! let path = encode(-128i16, 128, -3);
+//! assert_eq!(decode(-128i16, 128, &path), -3);
+//! # }
+//! ```
+//!
+//! ```rust
+//! # #[cfg(feature = "alloc")] {
 //! use bbse::{encode, BBSEStack};
 //! let mut stack = BBSEStack::new();
 //! for v in [0, 1, 2, 3, 4, 5, 6, 7] {
@@ -39,6 +58,7 @@
 //! }
 //! let decoded = stack.decode_all(0, 8);
 //! assert_eq!(decoded, vec![0, 1, 2, 3, 4, 5, 6, 7]);
+//! # }
 //! ```
 //!
 //! ## Limitations
@@ -48,162 +68,390 @@
 //! - Not optimized for random-access decoding without range knowledge.
 #![cfg_attr(not(feature = "std"), no_std)]
 
-#[cfg(not(feature = "std"))]
+#[cfg(all(feature = "alloc", not(feature = "std")))]
 extern crate alloc;
 
-#[cfg(not(feature = "std"))]
+#[cfg(feature = "alloc")]
+mod adaptive;
+mod code;
+#[cfg(feature = "alloc")]
+mod codec;
+mod error;
+#[cfg(feature = "alloc")]
+pub mod format;
+#[cfg(feature = "alloc")]
+pub mod image;
+#[cfg(feature = "alloc")]
+mod indexed;
+mod int;
+#[cfg(feature = "std")]
+mod io;
+#[cfg(feature = "alloc")]
+mod packed;
+#[cfg(feature = "alloc")]
+mod path;
+#[cfg(feature = "alloc")]
+mod record;
+mod search;
+#[cfg(feature = "serde")]
+mod serde_impl;
+#[cfg(feature = "alloc")]
+mod set;
+#[cfg(feature = "alloc")]
+mod sorted;
+mod strategy;
+#[cfg(feature = "alloc")]
+mod stream;
+#[cfg(feature = "alloc")]
+mod tree;
+#[cfg(feature = "alloc")]
+mod unbounded;
+
+#[cfg(feature = "alloc")]
+pub use adaptive::AdaptiveCoder;
+#[cfg(feature = "derive")]
+pub use bbse_derive::{BbseDecode, BbseEncode};
+pub use code::{
+    decode_const, decode_inline, encode_const, encode_inline, try_decode_inline, try_encode_inline,
+    BbseCode,
+};
+#[cfg(feature = "alloc")]
+pub use codec::{BbseDecode, BbseEncode};
+pub use error::BbseError;
+#[cfg(feature = "alloc")]
+pub use format::{Midpoint, StackLayout};
+#[cfg(feature = "alloc")]
+pub use indexed::IndexedSeq;
+pub use int::{BbseInt, IntValue};
+#[cfg(feature = "std")]
+pub use io::{BbseStreamReader, BbseStreamWriter};
+#[cfg(feature = "alloc")]
+pub use packed::PackedStack;
+#[cfg(feature = "alloc")]
+pub use path::BbsePath;
+#[cfg(feature = "alloc")]
+pub use record::{Record, RecordSchema};
+#[cfg(feature = "alloc")]
+pub use set::BbseSet;
+#[cfg(feature = "alloc")]
+pub use sorted::SortedSeq;
+#[cfg(feature = "alloc")]
+pub use strategy::{decode_with, encode_with, try_decode_with, try_encode_with};
+pub use strategy::{Center, MidpointStrategy, Ratio};
+#[cfg(feature = "alloc")]
+pub use stream::{BbseReader, BbseWriter};
+#[cfg(feature = "alloc")]
+pub use tree::SplitTree;
+#[cfg(feature = "alloc")]
+pub use unbounded::{
+    decode_unbounded, encode_unbounded, try_decode_unbounded, try_encode_unbounded,
+};
+
+#[cfg(all(feature = "alloc", not(feature = "std")))]
 use alloc::{vec, vec::Vec};
-use bitvec::order::Msb0;
-use bitvec::vec::BitVec;
+#[cfg(feature = "alloc")]
+use core::ops::{Bound, RangeBounds};
+#[cfg(feature = "alloc")]
 use core::{default::Default, option::Option, panic};
+#[cfg(feature = "alloc")]
+use search::Search;
 
-/// BBSE stack-based encoding: returns a BitVec representing the path
-pub fn encode(start: usize, end: usize, target: usize) -> BitVec<u8, Msb0> {
-    if start >= end {
-        panic!("Invalid range: start ({}) >= end ({})", start, end);
-    }
-    if !(start <= target && target < end) {
-        panic!("target ({}) out of bounds [{}, {})", target, start, end);
-    }
+/// BBSE stack-based encoding: returns a BbsePath representing the path
+///
+/// # Panics
+///
+/// Panics if the range is empty or `target` lies outside `[start, end)`.
+/// See [`try_encode`] for the non-panicking variant.
+#[cfg(feature = "alloc")]
+pub fn encode<T: BbseInt>(start: T, end: T, target: T) -> BbsePath {
+    try_encode(start, end, target).unwrap_or_else(|e| panic!("{}", e))
+}
 
-    let mut path = BitVec::<u8, Msb0>::new();
-    let mut lo = start;
-    let mut hi = end;
+/// Fallible version of [`encode`]
+#[cfg(feature = "alloc")]
+pub fn try_encode<T: BbseInt>(start: T, end: T, target: T) -> Result<BbsePath, BbseError> {
+    check_target(start, end, target)?;
+    let (lo, last) = keys(start, end);
 
-    loop {
-        let mid = (lo + hi) / 2;
+    let search = Search::new(lo, last, search::center(lo, last));
+    Ok(search::encode(search, target.to_key()).into())
+}
 
-        if target == mid {
-            break;
-        }
+/// BBSE custom midpoint (optional)
+///
+/// # Panics
+///
+/// Panics if the range is empty, `target` lies outside `[start, end)` or
+/// `midpoint` is not strictly inside `(start, end)`.
+/// See [`try_encode_from`] for the non-panicking variant.
+#[cfg(feature = "alloc")]
+pub fn encode_from<T: BbseInt>(start: T, end: T, target: T, midpoint: T) -> BbsePath {
+    try_encode_from(start, end, target, midpoint).unwrap_or_else(|e| panic!("{}", e))
+}
 
-        if target < mid {
-            path.push(false);
-            hi = mid;
-        } else {
-            path.push(true);
-            lo = mid;
-        }
+/// Fallible version of [`encode_from`]
+#[cfg(feature = "alloc")]
+pub fn try_encode_from<T: BbseInt>(
+    start: T,
+    end: T,
+    target: T,
+    midpoint: T,
+) -> Result<BbsePath, BbseError> {
+    check_target(start, end, target)?;
+    check_midpoint(start, end, midpoint)?;
+    let (lo, last) = keys(start, end);
 
-        if hi - lo == 1 {
-            break;
-        }
-    }
+    let search = Search::new(lo, last, midpoint.to_key());
+    Ok(search::encode(search, target.to_key()).into())
+}
 
-    path
+/// BBSE decoder: consumes a path and returns the corresponding value
+///
+/// The path is not validated and bits past the end of the search are ignored;
+/// see [`try_decode`] for a checked decoder.
+///
+/// # Panics
+///
+/// Panics if the range is empty.
+#[cfg(feature = "alloc")]
+pub fn decode<T: BbseInt>(start: T, end: T, path: &BbsePath) -> T {
+    check_range(start, end).unwrap_or_else(|e| panic!("{}", e));
+    let (lo, last) = keys(start, end);
+
+    let search = Search::new(lo, last, search::center(lo, last));
+    T::from_key(search::decode_lenient(search, path.iter()))
 }
 
-/// BBSE custom midpoint (optional)
-pub fn encode_from(start: usize, end: usize, target: usize, midpoint: usize) -> BitVec<u8, Msb0> {
-    if start >= end {
-        panic!("Invalid range: start ({}) >= end ({})", start, end);
-    }
-    if !(start <= target && target < end) {
-        panic!("target ({}) out of bounds [{}, {})", target, start, end);
-    }
-    if !(start < midpoint && midpoint < end) {
-        panic!(
-            "midpoint ({}) must be within (start={}, end={})",
-            midpoint, start, end
-        );
-    }
+/// Checked version of [`decode`]
+///
+/// The path is replayed with the same termination rules [`encode`] uses, so
+/// only paths that [`encode`] can produce for `[start, end)` are accepted.
+#[cfg(feature = "alloc")]
+pub fn try_decode<T: BbseInt>(start: T, end: T, path: &BbsePath) -> Result<T, BbseError> {
+    check_range(start, end)?;
+    let (lo, last) = keys(start, end);
 
-    let mut path = BitVec::<u8, Msb0>::new();
-    let mut lo = start;
-    let mut hi = end;
-    let mut mid = midpoint;
+    let search = Search::new(lo, last, search::center(lo, last));
+    search::decode_checked(search, path.iter()).map(T::from_key)
+}
 
-    loop {
-        if target == mid {
-            break;
-        }
+/// BBSE custom midpoint (optional for default midpoint encoding)
+///
+/// The path is not validated; see [`try_decode_from`] for a checked decoder.
+///
+/// # Panics
+///
+/// Panics if the range is empty or `midpoint` is not strictly inside `(start, end)`.
+#[cfg(feature = "alloc")]
+pub fn decode_from<T: BbseInt>(start: T, end: T, path: &BbsePath, midpoint: T) -> T {
+    check_midpoint(start, end, midpoint).unwrap_or_else(|e| panic!("{}", e));
+    let (lo, last) = keys(start, end);
 
-        if target < mid {
-            path.push(false);
-            hi = mid;
-        } else {
-            path.push(true);
-            lo = mid;
-        }
+    let search = Search::new(lo, last, midpoint.to_key());
+    T::from_key(search::decode_lenient(search, path.iter()))
+}
 
-        if hi - lo == 1 {
-            break;
-        }
+/// Checked version of [`decode_from`]
+///
+/// Only paths that [`encode_from`] can produce for the same range and
+/// midpoint are accepted.
+#[cfg(feature = "alloc")]
+pub fn try_decode_from<T: BbseInt>(
+    start: T,
+    end: T,
+    path: &BbsePath,
+    midpoint: T,
+) -> Result<T, BbseError> {
+    check_midpoint(start, end, midpoint)?;
+    let (lo, last) = keys(start, end);
 
-        mid = (lo + hi) / 2;
-    }
+    let search = Search::new(lo, last, midpoint.to_key());
+    search::decode_checked(search, path.iter()).map(T::from_key)
+}
 
-    path
+/// Checks that `path` is a path [`encode`] can produce for `[start, end)`
+#[cfg(feature = "alloc")]
+pub fn validate_path<T: BbseInt>(start: T, end: T, path: &BbsePath) -> Result<(), BbseError> {
+    try_decode(start, end, path).map(|_| ())
 }
 
-/// BBSE decoder: consumes a path and returns the corresponding value
-pub fn decode(start: usize, end: usize, path: &BitVec<u8, Msb0>) -> usize {
-    let mut lo = start;
-    let mut hi = end;
-
-    for bit in path.iter() {
-        let mid = (lo + hi) / 2;
-        if *bit {
-            lo = mid;
-        } else {
-            hi = mid;
-        }
-    }
+/// Checks that `path` is a path [`encode_from`] can produce for `[start, end)` and `midpoint`
+#[cfg(feature = "alloc")]
+pub fn validate_path_from<T: BbseInt>(
+    start: T,
+    end: T,
+    path: &BbsePath,
+    midpoint: T,
+) -> Result<(), BbseError> {
+    try_decode_from(start, end, path, midpoint).map(|_| ())
+}
 
-    (lo + hi) / 2
+/// [`encode`] over any range expression, e.g. `encode_range(0..=255u8, v)`
+///
+/// Paths are identical to [`encode`] over the equivalent half-open range, but
+/// the range may end at the top of the type (`u8::MIN..=u8::MAX`, `..`).
+///
+/// # Panics
+///
+/// Panics if the range is empty or does not contain `target`.
+/// See [`try_encode_range`] for the non-panicking variant.
+#[cfg(feature = "alloc")]
+pub fn encode_range<T: BbseInt>(range: impl RangeBounds<T>, target: T) -> BbsePath {
+    try_encode_range(range, target).unwrap_or_else(|e| panic!("{}", e))
 }
 
-/// BBSE custom midpoint (optional for default midpoint encoding)
-pub fn decode_from(start: usize, end: usize, path: &BitVec<u8, Msb0>, midpoint: usize) -> usize {
-    if path.is_empty() {
-        return midpoint;
-    }
+/// Fallible version of [`encode_range`]
+#[cfg(feature = "alloc")]
+pub fn try_encode_range<T: BbseInt>(
+    range: impl RangeBounds<T>,
+    target: T,
+) -> Result<BbsePath, BbseError> {
+    let (lo, last) = range_keys(&range)?;
+    let key = check_range_target(lo, last, target)?;
+
+    let search = Search::new(lo, last, search::center(lo, last));
+    Ok(search::encode(search, key).into())
+}
 
-    let mut lo = start;
-    let mut hi = end;
-    let mut mid = midpoint;
+/// [`decode`] over any range expression
+///
+/// The path is not validated; see [`try_decode_range`] for a checked decoder.
+///
+/// # Panics
+///
+/// Panics if the range is empty.
+#[cfg(feature = "alloc")]
+pub fn decode_range<T: BbseInt>(range: impl RangeBounds<T>, path: &BbsePath) -> T {
+    let (lo, last) = range_keys(&range).unwrap_or_else(|e| panic!("{}", e));
 
-    for bit in path.iter() {
-        if *bit {
-            lo = mid;
-        } else {
-            hi = mid;
-        }
+    let search = Search::new(lo, last, search::center(lo, last));
+    T::from_key(search::decode_lenient(search, path.iter()))
+}
+
+/// Checked version of [`decode_range`], see [`try_decode`]
+#[cfg(feature = "alloc")]
+pub fn try_decode_range<T: BbseInt>(
+    range: impl RangeBounds<T>,
+    path: &BbsePath,
+) -> Result<T, BbseError> {
+    let (lo, last) = range_keys(&range)?;
+
+    let search = Search::new(lo, last, search::center(lo, last));
+    search::decode_checked(search, path.iter()).map(T::from_key)
+}
 
-        if hi - lo == 1 {
-            break;
+/// Inclusive key bounds of a non-empty `[start, end)`.
+fn keys<T: BbseInt>(start: T, end: T) -> (u128, u128) {
+    (start.to_key(), end.to_key() - 1)
+}
+
+/// Inclusive key bounds of a range expression, or an error if it is empty.
+#[cfg(feature = "alloc")]
+fn range_keys<T: BbseInt>(range: &impl RangeBounds<T>) -> Result<(u128, u128), BbseError> {
+    let empty = || {
+        let bound = |b: Bound<&T>, unbounded: T| match b {
+            Bound::Included(&v) | Bound::Excluded(&v) => v,
+            Bound::Unbounded => unbounded,
+        };
+        BbseError::EmptyRange {
+            start: bound(range.start_bound(), T::MIN).into(),
+            end: bound(range.end_bound(), T::MAX).into(),
         }
+    };
+
+    let lo = match range.start_bound() {
+        Bound::Included(&v) => v.to_key(),
+        Bound::Excluded(&v) => v.to_key().checked_add(1).ok_or_else(empty)?,
+        Bound::Unbounded => T::MIN.to_key(),
+    };
+    let last = match range.end_bound() {
+        Bound::Included(&v) => v.to_key(),
+        Bound::Excluded(&v) => v.to_key().checked_sub(1).ok_or_else(empty)?,
+        Bound::Unbounded => T::MAX.to_key(),
+    };
 
-        mid = (lo + hi) / 2;
+    if lo > last {
+        return Err(empty());
     }
+    Ok((lo, last))
+}
 
-    (lo + hi) / 2
+#[cfg(feature = "alloc")]
+fn check_range_target<T: BbseInt>(lo: u128, last: u128, target: T) -> Result<u128, BbseError> {
+    let key = target.to_key();
+    if !(lo <= key && key <= last) {
+        return Err(BbseError::TargetOutOfRange {
+            target: target.into(),
+            first: T::from_key(lo).into(),
+            last: T::from_key(last).into(),
+        });
+    }
+    Ok(key)
+}
+
+fn check_range<T: BbseInt>(start: T, end: T) -> Result<(), BbseError> {
+    if start >= end {
+        return Err(BbseError::EmptyRange {
+            start: start.into(),
+            end: end.into(),
+        });
+    }
+    Ok(())
+}
+
+fn check_target<T: BbseInt>(start: T, end: T, target: T) -> Result<(), BbseError> {
+    check_range(start, end)?;
+    if !(start <= target && target < end) {
+        return Err(BbseError::TargetOutOfBounds {
+            target: target.into(),
+            start: start.into(),
+            end: end.into(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(feature = "alloc")]
+fn check_midpoint<T: BbseInt>(start: T, end: T, midpoint: T) -> Result<(), BbseError> {
+    check_range(start, end)?;
+    if !(start < midpoint && midpoint < end) {
+        return Err(BbseError::InvalidMidpoint {
+            midpoint: midpoint.into(),
+            start: start.into(),
+            end: end.into(),
+        });
+    }
+    Ok(())
 }
 
 /// Stack model — store multiple values as separate paths
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BBSEStack {
-    pub entries: Vec<BitVec<u8, Msb0>>,
+    pub entries: Vec<BbsePath>,
 }
+#[cfg(feature = "alloc")]
 impl Default for BBSEStack {
     fn default() -> Self {
         Self::new()
     }
 }
 
+#[cfg(feature = "alloc")]
 impl BBSEStack {
     pub fn new() -> Self {
         Self { entries: vec![] }
     }
 
-    pub fn push(&mut self, path: BitVec<u8, Msb0>) {
+    pub fn push(&mut self, path: BbsePath) {
         self.entries.push(path);
     }
 
-    pub fn pop(&mut self) -> Option<BitVec<u8, Msb0>> {
+    pub fn pop(&mut self) -> Option<BbsePath> {
         self.entries.pop()
     }
 
-    pub fn decode_all(&self, start: usize, end: usize) -> Vec<usize> {
+    pub fn decode_all<T: BbseInt>(&self, start: T, end: T) -> Vec<T> {
         self.entries.iter().map(|p| decode(start, end, p)).collect()
     }
 
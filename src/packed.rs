@@ -0,0 +1,198 @@
+//! Stack of BBSE values packed into one contiguous bitstream.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use bitvec::order::Msb0;
+use bitvec::vec::BitVec;
+use core::marker::PhantomData;
+
+use crate::search::{self, Search};
+use crate::{BbseError, BbseInt};
+
+/// Stack model — all values of one range share a single `BitVec`
+///
+/// Each value is stored as its prefix-free stream path (see [`BbseWriter`](crate::BbseWriter))
+/// with the bits reversed, so [`pop`](PackedStack::pop) can walk the last path
+/// backwards from the top of the buffer. No lengths or per-value headers are
+/// kept: [`bit_len`](PackedStack::bit_len) is exactly the sum of the path lengths.
+///
+/// ```rust
+/// use bbse::PackedStack;
+/// let mut stack = PackedStack::new(0u8, 100);
+/// for v in [3, 50, 99] {
+///     stack.push(v);
+/// }
+/// assert_eq!(stack.bit_len(), 19); // 6 or 7 bits per value
+/// assert_eq!(stack.pop(), Some(99));
+/// assert_eq!(stack.decode_all(), vec![3, 50]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackedStack<T> {
+    paths: PackedPaths<T>,
+    len: usize,
+}
+
+impl<T: BbseInt> PackedStack<T> {
+    /// Empty stack for values in `[start, end)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is empty. See [`try_new`](PackedStack::try_new).
+    pub fn new(start: T, end: T) -> Self {
+        Self::try_new(start, end).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of [`new`](PackedStack::new)
+    pub fn try_new(start: T, end: T) -> Result<Self, BbseError> {
+        Ok(Self {
+            paths: PackedPaths::new(start, end)?,
+            len: 0,
+        })
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `value` lies outside the stack's range. See [`try_push`](PackedStack::try_push).
+    pub fn push(&mut self, value: T) {
+        self.try_push(value).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of [`push`](PackedStack::push)
+    pub fn try_push(&mut self, value: T) -> Result<(), BbseError> {
+        let top = self.paths.push(value)?;
+        self.paths.reverse_from(top);
+        self.len += 1;
+        Ok(())
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        let (value, top) = self.paths.decode_reversed(self.paths.bit_len());
+        self.paths.truncate(top);
+        self.len -= 1;
+        Some(value)
+    }
+
+    pub fn peek(&self) -> Option<T> {
+        (self.len > 0).then(|| self.paths.decode_reversed(self.paths.bit_len()).0)
+    }
+
+    /// Decodes every value, bottom of the stack first.
+    pub fn decode_all(&self) -> Vec<T> {
+        let mut values = Vec::with_capacity(self.len);
+        let mut top = self.paths.bit_len();
+        for _ in 0..self.len {
+            let (value, below) = self.paths.decode_reversed(top);
+            values.push(value);
+            top = below;
+        }
+        values.reverse();
+        values
+    }
+
+    /// Number of values on the stack.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Exact number of bits used by the stored paths.
+    pub fn bit_len(&self) -> usize {
+        self.paths.bit_len()
+    }
+
+    /// Bytes the bit buffer occupies, rounded up to whole bytes.
+    pub fn byte_len(&self) -> usize {
+        self.paths.byte_len()
+    }
+
+    pub fn clear(&mut self) {
+        self.paths.clear();
+        self.len = 0;
+    }
+}
+
+/// Prefix-free paths of one range, back to back in a single `BitVec`
+///
+/// The storage shared by [`PackedStack`] and [`IndexedSeq`](crate::IndexedSeq).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PackedPaths<T> {
+    bits: BitVec<u8, Msb0>,
+    lo: u128,
+    last: u128,
+    _marker: PhantomData<T>,
+}
+
+impl<T: BbseInt> PackedPaths<T> {
+    /// Empty buffer for values in `[start, end)`.
+    pub(crate) fn new(start: T, end: T) -> Result<Self, BbseError> {
+        crate::check_range(start, end)?;
+        let (lo, last) = crate::keys(start, end);
+        Ok(Self {
+            bits: BitVec::new(),
+            lo,
+            last,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Appends the path to `value`; returns the bit offset it starts at.
+    pub(crate) fn push(&mut self, value: T) -> Result<usize, BbseError> {
+        crate::check_target(T::from_key(self.lo), T::from_key(self.last + 1), value)?;
+        let start = self.bits.len();
+        let search = self.search();
+        let bits = &mut self.bits;
+        search::encode_full(search, value.to_key(), |bit| bits.push(bit));
+        Ok(start)
+    }
+
+    /// Decodes the path that starts at bit `pos`; returns it with the next offset.
+    pub(crate) fn decode_at(&self, pos: usize) -> (T, usize) {
+        let mut pos = pos;
+        let key = search::decode_full(self.search(), || {
+            pos += 1;
+            Some(self.bits[pos - 1])
+        });
+        (T::from_key(key.expect("paths are complete")), pos)
+    }
+
+    /// Decodes the reversed path that ends at bit `top`; returns it with its start.
+    pub(crate) fn decode_reversed(&self, top: usize) -> (T, usize) {
+        let mut pos = top;
+        let key = search::decode_full(self.search(), || {
+            pos -= 1;
+            Some(self.bits[pos])
+        });
+        (T::from_key(key.expect("paths are complete")), pos)
+    }
+
+    /// Reverses the bits from `pos` to the end, see [`decode_reversed`](PackedPaths::decode_reversed).
+    pub(crate) fn reverse_from(&mut self, pos: usize) {
+        self.bits[pos..].reverse();
+    }
+
+    pub(crate) fn truncate(&mut self, len: usize) {
+        self.bits.truncate(len);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.bits.clear();
+    }
+
+    pub(crate) fn bit_len(&self) -> usize {
+        self.bits.len()
+    }
+
+    pub(crate) fn byte_len(&self) -> usize {
+        self.bits.as_raw_slice().len()
+    }
+
+    fn search(&self) -> Search {
+        Search::new(self.lo, self.last, search::center(self.lo, self.last))
+    }
+}
@@ -0,0 +1,158 @@
+use bitvec::order::Msb0;
+use bitvec::slice::BitSlice;
+use bitvec::vec::BitVec;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::BbseError;
+
+/// An encoded BBSE path: the sequence of left (`0`) / right (`1`) decisions.
+///
+/// Paths print and parse as strings of `0`/`1` characters:
+///
+/// ```rust
+/// use bbse::{encode, BbsePath};
+/// let path = encode(0, 8, 5);
+/// assert_eq!(path.to_string(), "10");
+/// assert_eq!("10".parse::<BbsePath>(), Ok(path));
+/// ```
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BbsePath {
+    /// Invariant: bits past `len()` in the last byte are zero.
+    bits: BitVec<u8, Msb0>,
+}
+
+impl BbsePath {
+    /// Empty path, which decodes to the first midpoint.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of decisions in the path.
+    pub fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    /// Decision at `index`: `true` for right, `false` for left.
+    pub fn get(&self, index: usize) -> Option<bool> {
+        self.bits.get(index).map(|bit| *bit)
+    }
+
+    pub fn push(&mut self, bit: bool) {
+        self.bits.push(bit);
+        self.bits.set_uninitialized(false);
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = bool> + ExactSizeIterator + '_ {
+        self.bits.iter().by_vals()
+    }
+
+    /// Whether `prefix` is a prefix of this path.
+    pub fn starts_with(&self, prefix: &BbsePath) -> bool {
+        self.bits.starts_with(&prefix.bits)
+    }
+
+    /// The bits packed most significant first; padding bits in the last byte are zero.
+    pub fn as_raw_slice(&self) -> &[u8] {
+        self.bits.as_raw_slice()
+    }
+
+    /// Rebuilds a path from [`as_raw_slice`](BbsePath::as_raw_slice) bytes and a bit length.
+    ///
+    /// Returns `None` if `len` needs more bytes than given.
+    pub fn from_raw_parts(bytes: &[u8], len: usize) -> Option<Self> {
+        let bits = BitSlice::<u8, Msb0>::from_slice(bytes).get(..len)?;
+        Some(BitVec::from_bitslice(bits).into())
+    }
+
+    pub(crate) fn as_bitslice(&self) -> &BitSlice<u8, Msb0> {
+        &self.bits
+    }
+
+    pub fn into_bitvec(self) -> BitVec<u8, Msb0> {
+        self.bits
+    }
+}
+
+impl From<BitVec<u8, Msb0>> for BbsePath {
+    fn from(mut bits: BitVec<u8, Msb0>) -> Self {
+        bits.set_uninitialized(false);
+        Self { bits }
+    }
+}
+
+impl From<&BitSlice<u8, Msb0>> for BbsePath {
+    fn from(bits: &BitSlice<u8, Msb0>) -> Self {
+        BitVec::from_bitslice(bits).into()
+    }
+}
+
+impl From<BbsePath> for BitVec<u8, Msb0> {
+    fn from(path: BbsePath) -> Self {
+        path.bits
+    }
+}
+
+impl FromIterator<bool> for BbsePath {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        iter.into_iter().collect::<BitVec<u8, Msb0>>().into()
+    }
+}
+
+impl Extend<bool> for BbsePath {
+    fn extend<I: IntoIterator<Item = bool>>(&mut self, iter: I) {
+        self.bits.extend(iter);
+        self.bits.set_uninitialized(false);
+    }
+}
+
+impl fmt::Display for BbsePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for bit in self.iter() {
+            f.write_str(if bit { "1" } else { "0" })?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for BbsePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BbsePath(\"{}\")", self)
+    }
+}
+
+impl FromStr for BbsePath {
+    type Err = BbseError;
+
+    fn from_str(s: &str) -> Result<Self, BbseError> {
+        s.bytes()
+            .map(|c| match c {
+                b'0' => Ok(false),
+                b'1' => Ok(true),
+                _ => Err(BbseError::InvalidFormat {
+                    reason: "a path may only contain '0' and '1'",
+                }),
+            })
+            .collect()
+    }
+}
+
+impl PartialEq<str> for BbsePath {
+    fn eq(&self, other: &str) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .zip(other.bytes())
+                .all(|(b, c)| c == b'0' + b as u8)
+    }
+}
+
+impl PartialEq<&str> for BbsePath {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
@@ -0,0 +1,240 @@
+//! Records of fields with their own ranges, coded into one bitstream.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::format::type_tag;
+use crate::{BbseError, BbseInt, BbsePath, BbseReader, BbseWriter};
+
+/// A named field: its integer type and the keys of `[start, end)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Field {
+    name: &'static str,
+    tag: u8,
+    start: u128,
+    end: u128,
+}
+
+/// Ordered list of field ranges that records are coded with
+///
+/// Each field is written as a prefix-free stream path over its own range, in
+/// declaration order, so a record costs the sum of its fields' paths and
+/// records can follow each other in one [`BbseWriter`] without lengths.
+///
+/// ```rust
+/// use bbse::RecordSchema;
+/// let schema = RecordSchema::new()
+///     .field("channel", 0u8, 3)
+///     .field("delta", -64i8, 64)
+///     .field("offset", 0u16, 1000);
+///
+/// let mut record = schema.record();
+/// record.set("channel", 2u8);
+/// record.set("delta", -5i8);
+/// record.set("offset", 730u16);
+/// let bits = schema.encode(&record);
+/// assert_eq!(bits.len(), 19); // 2 + 7 + 10 bits
+///
+/// let decoded = schema.decode(&bits).unwrap();
+/// assert_eq!(decoded.get::<i8>("delta"), -5);
+/// assert_eq!(decoded, record);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecordSchema {
+    fields: Vec<Field>,
+}
+
+impl RecordSchema {
+    /// Schema without fields.
+    pub fn new() -> Self {
+        Self { fields: Vec::new() }
+    }
+
+    /// Appends a field named `name` holding values of `T` from `[start, end)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is empty or `name` is already taken.
+    /// See [`try_field`](RecordSchema::try_field).
+    pub fn field<T: BbseInt>(self, name: &'static str, start: T, end: T) -> Self {
+        self.try_field(name, start, end)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of [`field`](RecordSchema::field)
+    pub fn try_field<T: BbseInt>(
+        mut self,
+        name: &'static str,
+        start: T,
+        end: T,
+    ) -> Result<Self, BbseError> {
+        crate::check_range(start, end)?;
+        if self.index_of(name).is_some() {
+            return Err(BbseError::DuplicateField { name });
+        }
+        self.fields.push(Field {
+            name,
+            tag: type_tag::<T>(),
+            start: start.to_key(),
+            end: end.to_key(),
+        });
+        Ok(self)
+    }
+
+    /// Number of fields.
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Position of the field called `name`.
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.fields.iter().position(|f| f.name == name)
+    }
+
+    /// Field names in declaration order.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.fields.iter().map(|f| f.name)
+    }
+
+    /// A record with every field set to the start of its range.
+    pub fn record(&self) -> Record<'_> {
+        Record {
+            schema: self,
+            keys: self.fields.iter().map(|f| f.start).collect(),
+        }
+    }
+
+    /// Appends every field of `record` to `writer`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `record` was made by a different schema.
+    pub fn write(&self, writer: &mut BbseWriter, record: &Record<'_>) {
+        assert!(
+            record.schema == self,
+            "record belongs to a different schema"
+        );
+        for (field, &key) in self.fields.iter().zip(&record.keys) {
+            writer.push(field.start, field.end - 1, key);
+        }
+    }
+
+    /// Reads one record from `reader`.
+    ///
+    /// On error the reader may have advanced past some of the fields.
+    pub fn read(&self, reader: &mut BbseReader<'_>) -> Result<Record<'_>, BbseError> {
+        let keys = self
+            .fields
+            .iter()
+            .map(|f| reader.pull_full(f.start, f.end - 1))
+            .collect::<Result<_, _>>()?;
+        Ok(Record { schema: self, keys })
+    }
+
+    /// Codes one record as its own path.
+    pub fn encode(&self, record: &Record<'_>) -> BbsePath {
+        let mut writer = BbseWriter::new();
+        self.write(&mut writer, record);
+        writer.into_path()
+    }
+
+    /// Decodes a path from [`encode`](RecordSchema::encode); it must hold exactly one record.
+    pub fn decode(&self, path: &BbsePath) -> Result<Record<'_>, BbseError> {
+        let mut reader = BbseReader::new(path);
+        let record = self.read(&mut reader)?;
+        if !reader.is_empty() {
+            return Err(BbseError::PathTooLong {
+                len: path.len(),
+                max: reader.position(),
+            });
+        }
+        Ok(record)
+    }
+
+    /// Appends the fields of every record in `records` to one path.
+    pub fn encode_all(&self, records: &[Record<'_>]) -> BbsePath {
+        let mut writer = BbseWriter::new();
+        for record in records {
+            self.write(&mut writer, record);
+        }
+        writer.into_path()
+    }
+
+    /// Decodes `count` records from a path made by [`encode_all`](RecordSchema::encode_all).
+    pub fn decode_all(&self, path: &BbsePath, count: usize) -> Result<Vec<Record<'_>>, BbseError> {
+        let mut reader = BbseReader::new(path);
+        let mut records = Vec::with_capacity(count);
+        for _ in 0..count {
+            records.push(self.read(&mut reader)?);
+        }
+        if !reader.is_empty() {
+            return Err(BbseError::PathTooLong {
+                len: path.len(),
+                max: reader.position(),
+            });
+        }
+        Ok(records)
+    }
+}
+
+/// Field values of one record of a [`RecordSchema`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record<'s> {
+    schema: &'s RecordSchema,
+    keys: Vec<u128>,
+}
+
+impl<'s> Record<'s> {
+    pub fn schema(&self) -> &'s RecordSchema {
+        self.schema
+    }
+
+    /// Value of the field called `name`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no such field or it holds another type than `T`.
+    /// See [`try_get`](Record::try_get).
+    pub fn get<T: BbseInt>(&self, name: &str) -> T {
+        self.try_get(name).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of [`get`](Record::get)
+    pub fn try_get<T: BbseInt>(&self, name: &str) -> Result<T, BbseError> {
+        let index = self.field::<T>(name)?;
+        Ok(T::from_key(self.keys[index]))
+    }
+
+    /// Sets the field called `name` to `value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no such field, it holds another type than `T` or
+    /// `value` lies outside its range. See [`try_set`](Record::try_set).
+    pub fn set<T: BbseInt>(&mut self, name: &str, value: T) {
+        self.try_set(name, value)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of [`set`](Record::set)
+    pub fn try_set<T: BbseInt>(&mut self, name: &str, value: T) -> Result<(), BbseError> {
+        let index = self.field::<T>(name)?;
+        let field = self.schema.fields[index];
+        crate::check_target(T::from_key(field.start), T::from_key(field.end), value)?;
+        self.keys[index] = value.to_key();
+        Ok(())
+    }
+
+    /// Index of the field called `name`, checked to hold `T`.
+    fn field<T: BbseInt>(&self, name: &str) -> Result<usize, BbseError> {
+        let index = self.schema.index_of(name).ok_or(BbseError::UnknownField)?;
+        if self.schema.fields[index].tag != type_tag::<T>() {
+            return Err(BbseError::FieldType { index });
+        }
+        Ok(index)
+    }
+}
@@ -0,0 +1,243 @@
+//! Binary search state shared by every encoder and decoder.
+//!
+//! Everything here works on [`BbseInt::to_key`](crate::BbseInt::to_key) keys and
+//! inclusive bounds `[lo, last]`, so a range may end at the very top of a type.
+
+use crate::{BbseCode, BbseError};
+
+/// Midpoint of `[lo, last]`, equal to `(lo + last + 1) / 2` without overflow.
+///
+/// For the half-open `[lo, hi)` this is the classic `(lo + hi) / 2`.
+pub(crate) const fn center(lo: u128, last: u128) -> u128 {
+    let span = last - lo;
+    lo + span / 2 + span % 2
+}
+
+/// One binary search in progress.
+#[derive(Clone, Copy)]
+pub(crate) struct Search {
+    pub(crate) lo: u128,
+    pub(crate) last: u128,
+    pub(crate) mid: u128,
+    /// Whether `lo` has already been compared against (it was a midpoint).
+    visited: bool,
+}
+
+impl Search {
+    pub(crate) const fn new(lo: u128, last: u128, mid: u128) -> Self {
+        Self {
+            lo,
+            last,
+            mid,
+            visited: false,
+        }
+    }
+
+    /// Whether a single value is left.
+    pub(crate) const fn is_done(&self) -> bool {
+        self.lo == self.last
+    }
+
+    /// Follows one bit: `false` keeps `[lo, mid)`, `true` keeps `[mid, last]`.
+    pub(crate) fn step(&mut self, bit: bool) {
+        *self = self.stepped(bit);
+    }
+
+    /// [`step`](Search::step) by value, for use in `const fn`.
+    pub(crate) const fn stepped(mut self, bit: bool) -> Self {
+        if bit {
+            self.lo = self.mid;
+            self.visited = true;
+        } else {
+            self.last = self.mid - 1;
+        }
+        self.mid = center(self.lo, self.last);
+        self
+    }
+
+    /// [`step`](Search::step), then moves the midpoint to `split(lo, last, depth)`
+    /// unless a single value is left.
+    pub(crate) fn step_split(
+        &mut self,
+        bit: bool,
+        depth: usize,
+        split: &mut impl FnMut(u128, u128, usize) -> Result<u128, BbseError>,
+    ) -> Result<(), BbseError> {
+        self.step(bit);
+        if !self.is_done() {
+            self.mid = split(self.lo, self.last, depth)?;
+        }
+        Ok(())
+    }
+
+    /// Whether the search ended on a value that was already a midpoint.
+    pub(crate) const fn is_revisit(&self) -> bool {
+        self.is_done() && self.visited
+    }
+}
+
+/// The path to `target`, stopping early when it becomes the midpoint.
+///
+/// A search over `u128` keys takes at most 128 steps, so the path always fits.
+pub(crate) const fn encode(mut search: Search, target: u128) -> BbseCode {
+    let mut code = BbseCode::new();
+    loop {
+        if target == search.mid {
+            break;
+        }
+
+        let bit = target > search.mid;
+        code = code.pushed(bit);
+        search = search.stepped(bit);
+
+        if search.is_done() {
+            break;
+        }
+    }
+    code
+}
+
+/// Emits the prefix-free path to `target`: the search never stops on the
+/// midpoint and always descends to a single value, with `mid` going right.
+#[cfg(feature = "alloc")]
+pub(crate) fn encode_full(mut search: Search, target: u128, mut push: impl FnMut(bool)) {
+    while !search.is_done() {
+        let bit = target >= search.mid;
+        push(bit);
+        search.step(bit);
+    }
+}
+
+/// [`encode`] with every midpoint below the root picked by `split`.
+#[cfg(feature = "alloc")]
+pub(crate) fn encode_split(
+    mut search: Search,
+    target: u128,
+    mut split: impl FnMut(u128, u128, usize) -> Result<u128, BbseError>,
+    mut push: impl FnMut(bool),
+) -> Result<(), BbseError> {
+    let mut depth = 0;
+    while target != search.mid {
+        let bit = target > search.mid;
+        push(bit);
+        depth += 1;
+        search.step_split(bit, depth, &mut split)?;
+
+        if search.is_done() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Reads a path written by [`encode_full`], pulling exactly as many bits as it needs.
+///
+/// Returns `None` if `next` runs out before the search ends.
+#[cfg(feature = "alloc")]
+pub(crate) fn decode_full(
+    mut search: Search,
+    mut next: impl FnMut() -> Option<bool>,
+) -> Option<u128> {
+    while !search.is_done() {
+        search.step(next()?);
+    }
+    Some(search.lo)
+}
+
+/// Last offset of the unbounded-code bucket starting at `lo = 2^k - 1`, which
+/// holds the `2^k` offsets up to `2 * lo`, or fewer if `max` comes first.
+#[cfg(feature = "alloc")]
+fn bucket_last(lo: u128, max: u128) -> u128 {
+    lo.checked_mul(2).map_or(max, |last| last.min(max))
+}
+
+/// Emits the self-delimiting code of the offset `n` in `[0, max]`: one `1` per
+/// bucket skipped and a `0` to stop, then the [`encode_full`] path within the
+/// bucket. The stop bit is left out in the last bucket.
+#[cfg(feature = "alloc")]
+pub(crate) fn encode_unbounded(n: u128, max: u128, mut push: impl FnMut(bool)) {
+    let mut lo = 0;
+    loop {
+        let last = bucket_last(lo, max);
+        if last < max {
+            let skip = n > last;
+            push(skip);
+            if skip {
+                lo = last + 1;
+                continue;
+            }
+        }
+        return encode_full(Search::new(lo, last, center(lo, last)), n, push);
+    }
+}
+
+/// Reads a code written by [`encode_unbounded`], pulling exactly as many bits as it needs.
+#[cfg(feature = "alloc")]
+pub(crate) fn decode_unbounded(max: u128, mut next: impl FnMut() -> Option<bool>) -> Option<u128> {
+    let mut lo = 0;
+    loop {
+        let last = bucket_last(lo, max);
+        if last < max && next()? {
+            lo = last + 1;
+            continue;
+        }
+        return decode_full(Search::new(lo, last, center(lo, last)), next);
+    }
+}
+
+/// Follows `path` without validating it; bits after the search ends are ignored.
+#[cfg(feature = "alloc")]
+pub(crate) fn decode_lenient(mut search: Search, path: impl IntoIterator<Item = bool>) -> u128 {
+    for bit in path {
+        if search.is_done() {
+            break;
+        }
+        search.step(bit);
+    }
+    search.mid
+}
+
+/// [`decode_lenient`] with every midpoint below the root picked by `split`.
+#[cfg(feature = "alloc")]
+pub(crate) fn decode_lenient_split(
+    mut search: Search,
+    path: impl IntoIterator<Item = bool>,
+    mut split: impl FnMut(u128, u128, usize) -> Result<u128, BbseError>,
+) -> Result<u128, BbseError> {
+    for (i, bit) in path.into_iter().enumerate() {
+        if search.is_done() {
+            break;
+        }
+        search.step_split(bit, i + 1, &mut split)?;
+    }
+    Ok(search.mid)
+}
+
+/// Follows `path`, accepting only paths [`encode`] can produce.
+pub(crate) fn decode_checked(
+    search: Search,
+    path: impl ExactSizeIterator<Item = bool>,
+) -> Result<u128, BbseError> {
+    decode_checked_split(search, path, |lo, last, _| Ok(center(lo, last)))
+}
+
+/// [`decode_checked`] with every midpoint below the root picked by `split`.
+pub(crate) fn decode_checked_split(
+    mut search: Search,
+    path: impl ExactSizeIterator<Item = bool>,
+    mut split: impl FnMut(u128, u128, usize) -> Result<u128, BbseError>,
+) -> Result<u128, BbseError> {
+    let len = path.len();
+    for (i, bit) in path.enumerate() {
+        if search.is_done() {
+            return Err(BbseError::PathTooLong { len, max: i });
+        }
+
+        search.step_split(bit, i + 1, &mut split)?;
+
+        if search.is_revisit() {
+            return Err(BbseError::UnreachableValue { position: i });
+        }
+    }
+    Ok(search.mid)
+}
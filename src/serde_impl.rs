@@ -0,0 +1,137 @@
+//! `serde` support, enabled with the `serde` feature.
+//!
+//! A path is written as a string of `0`/`1` characters for human-readable
+//! formats (JSON, TOML, ...) and as a `(bit_len, bytes)` tuple otherwise, with
+//! the bits packed most significant first. A [`BBSEStack`] is a sequence of paths.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::fmt;
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeTuple, Serializer};
+
+use crate::{BBSEStack, BbsePath};
+
+impl Serialize for BbsePath {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            let mut tuple = serializer.serialize_tuple(2)?;
+            tuple.serialize_element(&(self.len() as u64))?;
+            tuple.serialize_element(&Bytes(self.as_raw_slice()))?;
+            tuple.end()
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for BbsePath {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(BitStringVisitor)
+        } else {
+            deserializer.deserialize_tuple(2, PackedVisitor)
+        }
+    }
+}
+
+impl Serialize for BBSEStack {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.entries.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for BBSEStack {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(BBSEStack {
+            entries: Vec::deserialize(deserializer)?,
+        })
+    }
+}
+
+struct Bytes<'a>(&'a [u8]);
+
+impl Serialize for Bytes<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+struct BitStringVisitor;
+
+impl Visitor<'_> for BitStringVisitor {
+    type Value = BbsePath;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a string of '0' and '1' characters")
+    }
+
+    fn visit_str<E: de::Error>(self, text: &str) -> Result<BbsePath, E> {
+        text.parse()
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(text), &self))
+    }
+}
+
+struct PackedVisitor;
+
+impl<'de> Visitor<'de> for PackedVisitor {
+    type Value = BbsePath;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a (bit length, bytes) pair")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<BbsePath, A::Error> {
+        let len: u64 = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let ByteBuf(bytes) = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+
+        usize::try_from(len)
+            .ok()
+            .filter(|&len| len.div_ceil(8) == bytes.len())
+            .and_then(|len| BbsePath::from_raw_parts(&bytes, len))
+            .ok_or_else(|| {
+                de::Error::invalid_value(
+                    de::Unexpected::Unsigned(len),
+                    &"bit length matching bytes",
+                )
+            })
+    }
+}
+
+struct ByteBuf(Vec<u8>);
+
+impl<'de> Deserialize<'de> for ByteBuf {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_byte_buf(ByteBufVisitor)
+    }
+}
+
+struct ByteBufVisitor;
+
+impl<'de> Visitor<'de> for ByteBufVisitor {
+    type Value = ByteBuf;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("bytes")
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<ByteBuf, E> {
+        Ok(ByteBuf(bytes.to_vec()))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, bytes: Vec<u8>) -> Result<ByteBuf, E> {
+        Ok(ByteBuf(bytes))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<ByteBuf, A::Error> {
+        let mut bytes = Vec::new();
+        while let Some(b) = seq.next_element()? {
+            bytes.push(b);
+        }
+        Ok(ByteBuf(bytes))
+    }
+}
@@ -0,0 +1,123 @@
+//! Sets of integers from a bounded universe.
+
+#[cfg(not(feature = "std"))]
+use alloc::{collections::BTreeSet, vec, vec::Vec};
+#[cfg(feature = "std")]
+use std::{collections::BTreeSet, vec};
+
+use crate::unbounded::from_offset;
+use crate::{BbseError, BbseInt, BbsePath, SortedSeq};
+
+/// A set of values from `[0, universe)`, coded as one bitstream
+///
+/// The members are stored as a strictly increasing [`SortedSeq`], so a set
+/// costs little when it is sparse, clustered or nearly full: a set holding
+/// the whole universe takes no bits at all. Use it in place of a bitmap when
+/// `universe` bits are too many.
+///
+/// ```rust
+/// use bbse::BbseSet;
+/// let enabled = BbseSet::new(4096u16, &[12, 13, 14, 15, 2048]);
+/// assert!(enabled.contains(14));
+/// assert!(!enabled.contains(16));
+/// assert_eq!(enabled.len(), 5);
+/// assert_eq!(enabled.bit_len(), 42); // a bitmap takes 4096
+/// assert_eq!(enabled.iter().collect::<Vec<_>>(), [12, 13, 14, 15, 2048]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BbseSet<T> {
+    seq: SortedSeq<T>,
+}
+
+impl<T: BbseInt> BbseSet<T> {
+    /// Codes the sorted `values`, all within `[0, universe)`; duplicates are stored once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `universe` is not positive, a value lies outside it or the
+    /// values are not sorted. See [`try_new`](BbseSet::try_new).
+    pub fn new(universe: T, values: &[T]) -> Self {
+        Self::try_new(universe, values).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of [`new`](BbseSet::new)
+    pub fn try_new(universe: T, values: &[T]) -> Result<Self, BbseError> {
+        let mut values = values.to_vec();
+        if let Some(position) = values.windows(2).position(|w| w[1] < w[0]) {
+            return Err(BbseError::NotSorted {
+                position: position + 1,
+            });
+        }
+        values.dedup();
+        Ok(Self {
+            seq: SortedSeq::try_new(from_offset(0), universe, &values)?,
+        })
+    }
+
+    /// Codes the members of `set`, all within `[0, universe)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `universe` is not positive or a member lies outside it.
+    /// See [`try_from_set`](BbseSet::try_from_set).
+    pub fn from_set(universe: T, set: &BTreeSet<T>) -> Self {
+        Self::try_from_set(universe, set).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of [`from_set`](BbseSet::from_set)
+    pub fn try_from_set(universe: T, set: &BTreeSet<T>) -> Result<Self, BbseError> {
+        let values: Vec<T> = set.iter().copied().collect();
+        Self::try_new(universe, &values)
+    }
+
+    /// Rebuilds a set from its universe, cardinality and bits, e.g. after storing them.
+    ///
+    /// Fails if `len` exceeds the universe or `bits` does not hold exactly
+    /// `len` members.
+    pub fn from_parts(universe: T, len: usize, bits: BbsePath) -> Result<Self, BbseError> {
+        Ok(Self {
+            seq: SortedSeq::from_parts(from_offset(0), universe, len, true, bits)?,
+        })
+    }
+
+    pub fn universe(&self) -> T {
+        self.seq.end()
+    }
+
+    /// Number of members.
+    pub fn len(&self) -> usize {
+        self.seq.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seq.is_empty()
+    }
+
+    /// Whether `value` is a member. See [`SortedSeq::contains`] for the cost.
+    pub fn contains(&self, value: T) -> bool {
+        self.seq.contains(value)
+    }
+
+    /// The members in increasing order; the whole set is decoded up front.
+    pub fn iter(&self) -> vec::IntoIter<T> {
+        self.seq.decode().into_iter()
+    }
+
+    /// Decodes the members into a [`BTreeSet`].
+    pub fn to_set(&self) -> BTreeSet<T> {
+        self.iter().collect()
+    }
+
+    /// Size of the coded set in bits.
+    pub fn bit_len(&self) -> usize {
+        self.seq.bit_len()
+    }
+
+    pub fn as_path(&self) -> &BbsePath {
+        self.seq.as_path()
+    }
+
+    pub fn into_path(self) -> BbsePath {
+        self.seq.into_path()
+    }
+}
@@ -0,0 +1,307 @@
+//! Sorted sequences coded with shrinking ranges (binary interpolative coding).
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+use crate::search::{self, Search};
+use crate::{BbseError, BbseInt, BbsePath};
+
+/// A sorted sequence of values from `[start, end)`, coded as one bitstream
+///
+/// The middle element is written first, within the whole range; the halves
+/// before and after it follow recursively, each within the range left between
+/// the values already written. Every element is written with the prefix-free
+/// [`BbseWriter`](crate::BbseWriter) search over its bounds, so elements whose
+/// bounds pin them down cost nothing. Dense runs and clustered values, as in
+/// posting lists or sorted ID sets, take far fewer bits than independent paths.
+///
+/// Strictly increasing sequences are detected and use tighter bounds, since
+/// `k` distinct values still have to fit to the left and right of an element.
+///
+/// ```rust
+/// use bbse::SortedSeq;
+/// let ids = [3u32, 4, 5, 6, 7, 8, 9, 10, 500, 501];
+/// let seq = SortedSeq::new(0, 1024, &ids);
+/// assert!(seq.is_strict());
+/// assert_eq!(seq.bit_len(), 43); // 81 bits as ten separate paths
+/// assert_eq!(seq.decode(), ids);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortedSeq<T> {
+    start: T,
+    end: T,
+    len: usize,
+    strict: bool,
+    bits: BbsePath,
+}
+
+impl<T: BbseInt> SortedSeq<T> {
+    /// Codes the non-decreasing `values`, all within `[start, end)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is empty, a value lies outside it or the values are
+    /// not sorted. See [`try_new`](SortedSeq::try_new).
+    pub fn new(start: T, end: T, values: &[T]) -> Self {
+        Self::try_new(start, end, values).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of [`new`](SortedSeq::new)
+    pub fn try_new(start: T, end: T, values: &[T]) -> Result<Self, BbseError> {
+        crate::check_range(start, end)?;
+        for (i, &v) in values.iter().enumerate() {
+            crate::check_target(start, end, v)?;
+            if i > 0 && v < values[i - 1] {
+                return Err(BbseError::NotSorted { position: i });
+            }
+        }
+        let strict = values.windows(2).all(|w| w[0] < w[1]);
+
+        let keys: Vec<u128> = values.iter().map(|v| v.to_key()).collect();
+        let (lo, last) = crate::keys(start, end);
+        let mut bits = BbsePath::new();
+        write(&keys, lo, last, strict as u128, &mut |bit| bits.push(bit));
+
+        Ok(Self {
+            start,
+            end,
+            len: values.len(),
+            strict,
+            bits,
+        })
+    }
+
+    /// Rebuilds a sequence from the parts of another one, e.g. after storing them.
+    ///
+    /// Fails if `bits` does not hold exactly `len` values. The bits are checked
+    /// without decoding the values into memory, so an oversized `len` fails
+    /// before anything is allocated.
+    pub fn from_parts(
+        start: T,
+        end: T,
+        len: usize,
+        strict: bool,
+        bits: BbsePath,
+    ) -> Result<Self, BbseError> {
+        crate::check_range(start, end)?;
+        if strict && len as u128 > end.to_key() - start.to_key() {
+            return Err(BbseError::InvalidFormat {
+                reason: "more distinct values than the range holds",
+            });
+        }
+        let seq = Self {
+            start,
+            end,
+            len,
+            strict,
+            bits,
+        };
+        let used = seq.skip_all()?;
+        if used < seq.bits.len() {
+            return Err(BbseError::PathTooLong {
+                len: seq.bits.len(),
+                max: used,
+            });
+        }
+        Ok(seq)
+    }
+
+    /// All values, in order.
+    pub fn decode(&self) -> Vec<T> {
+        let (lo, last) = crate::keys(self.start, self.end);
+        let mut keys = vec![0; self.len];
+        let mut bits = self.bits.iter();
+        read(&mut keys, lo, last, self.strict as u128, &mut || {
+            bits.next()
+        })
+        .expect("a sequence always holds its own values");
+        keys.into_iter().map(T::from_key).collect()
+    }
+
+    /// Whether `value` is in the sequence.
+    ///
+    /// Decodes only the elements on the way to `value`, but a value in the
+    /// second half of a subsequence still costs decoding the first half.
+    pub fn contains(&self, value: T) -> bool {
+        let (lo, last) = crate::keys(self.start, self.end);
+        let key = value.to_key();
+        if key < lo || key > last {
+            return false;
+        }
+        let mut bits = self.bits.iter();
+        seek(self.len, lo, last, self.strict as u128, key, &mut || {
+            bits.next()
+        })
+        .expect("a sequence always holds its own values")
+    }
+
+    /// Walks past every value, returning the number of bits used.
+    fn skip_all(&self) -> Result<usize, BbseError> {
+        let (lo, last) = crate::keys(self.start, self.end);
+        let mut bits = self.bits.iter();
+        let mut used = 0;
+        skip(self.len, lo, last, self.strict as u128, &mut || {
+            used += 1;
+            bits.next()
+        })
+        .ok_or(BbseError::UnexpectedEnd { position: 0 })?;
+        Ok(used)
+    }
+
+    pub fn start(&self) -> T {
+        self.start
+    }
+
+    pub fn end(&self) -> T {
+        self.end
+    }
+
+    /// Number of values.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Whether the values are strictly increasing.
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Size of the coded sequence in bits.
+    pub fn bit_len(&self) -> usize {
+        self.bits.len()
+    }
+
+    pub fn as_path(&self) -> &BbsePath {
+        &self.bits
+    }
+
+    pub fn into_path(self) -> BbsePath {
+        self.bits
+    }
+}
+
+/// Writes the middle of `keys` within `[lo, last]`, then each half.
+///
+/// `gap` is `1` for strictly increasing keys: every key then leaves room for
+/// its distinct neighbours.
+fn write(keys: &[u128], lo: u128, last: u128, gap: u128, push: &mut impl FnMut(bool)) {
+    if keys.is_empty() {
+        return;
+    }
+    let m = keys.len() / 2;
+    let first = lo + m as u128 * gap;
+    let limit = last - (keys.len() - 1 - m) as u128 * gap;
+    search::encode_full(
+        Search::new(first, limit, search::center(first, limit)),
+        keys[m],
+        &mut *push,
+    );
+
+    if m > 0 {
+        write(&keys[..m], lo, keys[m] - gap, gap, push);
+    }
+    if m + 1 < keys.len() {
+        write(&keys[m + 1..], keys[m] + gap, last, gap, push);
+    }
+}
+
+/// Reads what [`write`] wrote into `keys`, or `None` if the bits run out.
+fn read(
+    keys: &mut [u128],
+    lo: u128,
+    last: u128,
+    gap: u128,
+    next: &mut impl FnMut() -> Option<bool>,
+) -> Option<()> {
+    if keys.is_empty() {
+        return Some(());
+    }
+    let m = keys.len() / 2;
+    let first = lo + m as u128 * gap;
+    let limit = last - (keys.len() - 1 - m) as u128 * gap;
+    keys[m] = search::decode_full(
+        Search::new(first, limit, search::center(first, limit)),
+        &mut *next,
+    )?;
+
+    let (left, rest) = keys.split_at_mut(m);
+    let (mid, right) = (rest[0], &mut rest[1..]);
+    if !left.is_empty() {
+        read(left, lo, mid - gap, gap, next)?;
+    }
+    if !right.is_empty() {
+        read(right, mid + gap, last, gap, next)?;
+    }
+    Some(())
+}
+
+/// Looks for `key` among `len` values written by [`write`] within `[lo, last]`.
+fn seek(
+    len: usize,
+    lo: u128,
+    last: u128,
+    gap: u128,
+    key: u128,
+    next: &mut impl FnMut() -> Option<bool>,
+) -> Option<bool> {
+    if len == 0 {
+        return Some(false);
+    }
+    let m = len / 2;
+    let first = lo + m as u128 * gap;
+    let limit = last - (len - 1 - m) as u128 * gap;
+    let mid = search::decode_full(
+        Search::new(first, limit, search::center(first, limit)),
+        &mut *next,
+    )?;
+
+    if key == mid {
+        Some(true)
+    } else if key < mid {
+        Some(m > 0 && seek(m, lo, mid - gap, gap, key, next)?)
+    } else if m + 1 < len {
+        if m > 0 {
+            skip(m, lo, mid - gap, gap, next)?;
+        }
+        seek(len - 1 - m, mid + gap, last, gap, key, next)
+    } else {
+        Some(false)
+    }
+}
+
+/// Walks past `len` values written by [`write`] within `[lo, last]` without
+/// storing them, or `None` if the bits run out.
+fn skip(
+    len: usize,
+    lo: u128,
+    last: u128,
+    gap: u128,
+    next: &mut impl FnMut() -> Option<bool>,
+) -> Option<()> {
+    if len == 0 {
+        return Some(());
+    }
+    let m = len / 2;
+    let first = lo + m as u128 * gap;
+    let limit = last - (len - 1 - m) as u128 * gap;
+    if first == limit {
+        // The bounds pin down every value of this subsequence: no bits.
+        return Some(());
+    }
+    let mid = search::decode_full(
+        Search::new(first, limit, search::center(first, limit)),
+        &mut *next,
+    )?;
+
+    if m > 0 {
+        skip(m, lo, mid - gap, gap, next)?;
+    }
+    if m + 1 < len {
+        skip(len - 1 - m, mid + gap, last, gap, next)?;
+    }
+    Some(())
+}
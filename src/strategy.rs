@@ -0,0 +1,203 @@
+//! Pluggable midpoint strategies.
+//!
+//! [`encode`](crate::encode) splits every level of the search at the center and
+//! [`encode_from`](crate::encode_from) only moves the first split. A
+//! [`MidpointStrategy`] picks the split at every level, which lets a path
+//! favour whichever values are common in the data.
+
+use crate::search;
+#[cfg(feature = "alloc")]
+use crate::search::Search;
+use crate::BbseInt;
+#[cfg(feature = "alloc")]
+use crate::{BbseError, BbsePath};
+
+/// Chooses where the search splits the values that are left.
+///
+/// `split` receives the remaining values as the inclusive range `[lo, hi]`,
+/// which always holds at least two values, and the number of decisions taken
+/// so far (`0` for the root). It must return a midpoint with `lo < mid <= hi`:
+/// values below `mid` go left, the rest go right, and a path to `mid` itself
+/// stops right there.
+///
+/// Closures `Fn(T, T, usize) -> T` are strategies too:
+///
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// use bbse::{decode_with, encode_with};
+/// // Always split just above `lo`: small values get short paths.
+/// let unary = |lo: u8, _hi: u8, _depth: usize| lo + 1;
+/// let path = encode_with(0u8, 100, 1, &unary);
+/// assert_eq!(path.to_string(), "");
+/// assert_eq!(encode_with(0u8, 100, 3, &unary).to_string(), "11");
+/// assert_eq!(decode_with(0u8, 100, &path, &unary), 1);
+/// # }
+/// ```
+pub trait MidpointStrategy<T: BbseInt> {
+    fn split(&self, lo: T, hi: T, depth: usize) -> T;
+}
+
+impl<T: BbseInt, F: Fn(T, T, usize) -> T> MidpointStrategy<T> for F {
+    fn split(&self, lo: T, hi: T, depth: usize) -> T {
+        self(lo, hi, depth)
+    }
+}
+
+/// The classic split at `(lo + hi + 1) / 2` that [`encode`](crate::encode) uses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Center;
+
+impl<T: BbseInt> MidpointStrategy<T> for Center {
+    fn split(&self, lo: T, hi: T, _depth: usize) -> T {
+        T::from_key(search::center(lo.to_key(), hi.to_key()))
+    }
+}
+
+/// Splits at a fixed fraction `num / den` of the way from `lo` to `hi`, rounded up.
+///
+/// A fraction below one half is left-biased: the left side is smaller, so low
+/// values get shorter paths. `Ratio::HALF` is the same as [`Center`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ratio {
+    num: u64,
+    den: u64,
+}
+
+impl Ratio {
+    pub const HALF: Ratio = Ratio::new(1, 2);
+    /// `1 / φ²` ≈ 0.382, the smaller golden section.
+    pub const GOLDEN: Ratio = Ratio::new(381_966_011, 1_000_000_000);
+
+    /// # Panics
+    ///
+    /// Panics if `den` is zero or `num > den`.
+    pub const fn new(num: u64, den: u64) -> Self {
+        assert!(den > 0 && num <= den, "ratio must lie within [0, 1]");
+        Self { num, den }
+    }
+
+    /// The mirrored fraction `1 - num / den`, biased towards the other side.
+    pub const fn mirror(self) -> Self {
+        Self::new(self.den - self.num, self.den)
+    }
+}
+
+impl<T: BbseInt> MidpointStrategy<T> for Ratio {
+    fn split(&self, lo: T, hi: T, _depth: usize) -> T {
+        let (lo, span) = (lo.to_key(), hi.to_key() - lo.to_key());
+        let (num, den) = (u128::from(self.num), u128::from(self.den));
+        let offset = span / den * num + (span % den * num).div_ceil(den);
+        T::from_key(lo + offset.clamp(1, span))
+    }
+}
+
+/// Asks `strategy` for the midpoint of `[lo, last]`, checking the result.
+#[cfg(feature = "alloc")]
+fn splitter<'a, T: BbseInt, S: MidpointStrategy<T> + ?Sized>(
+    strategy: &'a S,
+) -> impl FnMut(u128, u128, usize) -> Result<u128, BbseError> + 'a {
+    |lo, last, depth| {
+        let (lo, hi) = (T::from_key(lo), T::from_key(last));
+        let mid = strategy.split(lo, hi, depth);
+        if !(lo < mid && mid <= hi) {
+            return Err(BbseError::InvalidSplit {
+                midpoint: mid.into(),
+                lo: lo.into(),
+                hi: hi.into(),
+                depth,
+            });
+        }
+        Ok(mid.to_key())
+    }
+}
+
+/// The search over `[start, end)` with its root split by `strategy`.
+#[cfg(feature = "alloc")]
+fn root<T: BbseInt, S: MidpointStrategy<T> + ?Sized>(
+    start: T,
+    end: T,
+    strategy: &S,
+) -> Result<Search, BbseError> {
+    crate::check_range(start, end)?;
+    let (lo, last) = crate::keys(start, end);
+
+    let mid = if lo == last {
+        lo
+    } else {
+        splitter(strategy)(lo, last, 0)?
+    };
+    Ok(Search::new(lo, last, mid))
+}
+
+/// [`encode`](crate::encode) with every midpoint chosen by `strategy`
+///
+/// Paths may be longer than with [`Center`]: a strategy that always splits
+/// next to `lo` needs one bit per skipped value.
+///
+/// # Panics
+///
+/// Panics if the range is empty, `target` lies outside `[start, end)` or the
+/// strategy returns a midpoint outside `(lo, hi]`.
+/// See [`try_encode_with`] for the non-panicking variant.
+#[cfg(feature = "alloc")]
+pub fn encode_with<T: BbseInt, S: MidpointStrategy<T> + ?Sized>(
+    start: T,
+    end: T,
+    target: T,
+    strategy: &S,
+) -> BbsePath {
+    try_encode_with(start, end, target, strategy).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Fallible version of [`encode_with`]
+#[cfg(feature = "alloc")]
+pub fn try_encode_with<T: BbseInt, S: MidpointStrategy<T> + ?Sized>(
+    start: T,
+    end: T,
+    target: T,
+    strategy: &S,
+) -> Result<BbsePath, BbseError> {
+    crate::check_target(start, end, target)?;
+    let search = root(start, end, strategy)?;
+
+    let mut path = BbsePath::new();
+    search::encode_split(search, target.to_key(), splitter(strategy), |bit| {
+        path.push(bit)
+    })?;
+    Ok(path)
+}
+
+/// [`decode`](crate::decode) with every midpoint chosen by `strategy`
+///
+/// The path is not validated; see [`try_decode_with`] for a checked decoder.
+///
+/// # Panics
+///
+/// Panics if the range is empty or the strategy returns a midpoint outside `(lo, hi]`.
+#[cfg(feature = "alloc")]
+pub fn decode_with<T: BbseInt, S: MidpointStrategy<T> + ?Sized>(
+    start: T,
+    end: T,
+    path: &BbsePath,
+    strategy: &S,
+) -> T {
+    root(start, end, strategy)
+        .and_then(|search| search::decode_lenient_split(search, path.iter(), splitter(strategy)))
+        .map(T::from_key)
+        .unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Checked version of [`decode_with`]
+///
+/// Only paths that [`encode_with`] can produce for the same range and
+/// strategy are accepted.
+#[cfg(feature = "alloc")]
+pub fn try_decode_with<T: BbseInt, S: MidpointStrategy<T> + ?Sized>(
+    start: T,
+    end: T,
+    path: &BbsePath,
+    strategy: &S,
+) -> Result<T, BbseError> {
+    let search = root(start, end, strategy)?;
+    search::decode_checked_split(search, path.iter(), splitter(strategy)).map(T::from_key)
+}
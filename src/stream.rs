@@ -0,0 +1,188 @@
+//! Concatenated bitstreams of prefix-free BBSE paths.
+//!
+//! A plain [`encode`](crate::encode) path stops as soon as the target becomes the
+//! midpoint, so the midpoint's path is empty and the path of one value can be a
+//! prefix of another. Inside a stream the search instead always descends until
+//! a single value is left, sending the midpoint right. The resulting paths form
+//! a prefix-free code of `floor(log2 n)` or `ceil(log2 n)` bits for a range of
+//! `n` values, and any number of them can share one buffer without lengths.
+
+use core::ops::RangeBounds;
+
+use crate::search::{self, Search};
+use crate::{BbseError, BbseInt, BbsePath};
+
+/// Writes values back-to-back into a single bit buffer.
+///
+/// ```rust
+/// use bbse::{BbseReader, BbseWriter};
+/// let mut writer = BbseWriter::new();
+/// writer.write(0, 3, 2).unwrap();
+/// writer.write(-64i8, 64, -5).unwrap();
+/// let path = writer.into_path();
+///
+/// let mut reader = BbseReader::new(&path);
+/// assert_eq!(reader.read(0, 3), Ok(2));
+/// assert_eq!(reader.read(-64i8, 64), Ok(-5));
+/// assert!(reader.is_empty());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BbseWriter {
+    bits: BbsePath,
+}
+
+impl BbseWriter {
+    pub fn new() -> Self {
+        Self {
+            bits: BbsePath::new(),
+        }
+    }
+
+    /// Appends `value` from `[start, end)`.
+    pub fn write<T: BbseInt>(&mut self, start: T, end: T, value: T) -> Result<(), BbseError> {
+        crate::check_target(start, end, value)?;
+        let (lo, last) = crate::keys(start, end);
+        self.push(lo, last, value.to_key());
+        Ok(())
+    }
+
+    /// Appends `value` from `[start, end)`, splitting first at `midpoint`,
+    /// see [`encode_from`](crate::encode_from).
+    pub fn write_from<T: BbseInt>(
+        &mut self,
+        start: T,
+        end: T,
+        value: T,
+        midpoint: T,
+    ) -> Result<(), BbseError> {
+        crate::check_target(start, end, value)?;
+        crate::check_midpoint(start, end, midpoint)?;
+        let (lo, last) = crate::keys(start, end);
+        self.push_search(Search::new(lo, last, midpoint.to_key()), value.to_key());
+        Ok(())
+    }
+
+    /// Appends `value` from any range expression, see [`encode_range`](crate::encode_range).
+    pub fn write_range<T: BbseInt>(
+        &mut self,
+        range: impl RangeBounds<T>,
+        value: T,
+    ) -> Result<(), BbseError> {
+        let (lo, last) = crate::range_keys(&range)?;
+        let key = crate::check_range_target(lo, last, value)?;
+        self.push(lo, last, key);
+        Ok(())
+    }
+
+    /// Appends a non-negative `value` with no upper bound, see [`encode_unbounded`](crate::encode_unbounded).
+    pub fn write_unbounded<T: BbseInt>(&mut self, value: T) -> Result<(), BbseError> {
+        let offset = crate::unbounded::offset(value)?;
+        let bits = &mut self.bits;
+        search::encode_unbounded(offset, crate::unbounded::max_offset::<T>(), |bit| {
+            bits.push(bit)
+        });
+        Ok(())
+    }
+
+    pub(crate) fn push(&mut self, lo: u128, last: u128, key: u128) {
+        self.push_search(Search::new(lo, last, search::center(lo, last)), key);
+    }
+
+    fn push_search(&mut self, search: Search, key: u128) {
+        let bits = &mut self.bits;
+        search::encode_full(search, key, |bit| bits.push(bit));
+    }
+
+    /// Number of bits written so far.
+    pub fn bit_len(&self) -> usize {
+        self.bits.len()
+    }
+
+    /// Everything written so far, as one path.
+    pub fn as_path(&self) -> &BbsePath {
+        &self.bits
+    }
+
+    pub fn into_path(self) -> BbsePath {
+        self.bits
+    }
+}
+
+/// Reads values written by [`BbseWriter`], in the same order and with the same ranges.
+#[derive(Debug, Clone)]
+pub struct BbseReader<'a> {
+    bits: &'a BbsePath,
+    pos: usize,
+}
+
+impl<'a> BbseReader<'a> {
+    pub fn new(bits: &'a BbsePath) -> Self {
+        Self { bits, pos: 0 }
+    }
+
+    /// Reads the next value from `[start, end)`.
+    ///
+    /// On error the reader does not advance.
+    pub fn read<T: BbseInt>(&mut self, start: T, end: T) -> Result<T, BbseError> {
+        crate::check_range(start, end)?;
+        let (lo, last) = crate::keys(start, end);
+        self.pull_full(lo, last).map(T::from_key)
+    }
+
+    /// Reads the next value written by [`BbseWriter::write_from`] with the same `midpoint`.
+    pub fn read_from<T: BbseInt>(&mut self, start: T, end: T, midpoint: T) -> Result<T, BbseError> {
+        crate::check_midpoint(start, end, midpoint)?;
+        let (lo, last) = crate::keys(start, end);
+        let search = Search::new(lo, last, midpoint.to_key());
+        self.pull(|next| search::decode_full(search, next))
+            .map(T::from_key)
+    }
+
+    /// Reads the next value from any range expression.
+    pub fn read_range<T: BbseInt>(&mut self, range: impl RangeBounds<T>) -> Result<T, BbseError> {
+        let (lo, last) = crate::range_keys(&range)?;
+        self.pull_full(lo, last).map(T::from_key)
+    }
+
+    /// Reads the next value written by [`BbseWriter::write_unbounded`].
+    pub fn read_unbounded<T: BbseInt>(&mut self) -> Result<T, BbseError> {
+        let max = crate::unbounded::max_offset::<T>();
+        self.pull(|next| search::decode_unbounded(max, next))
+            .map(crate::unbounded::from_offset)
+    }
+
+    pub(crate) fn pull_full(&mut self, lo: u128, last: u128) -> Result<u128, BbseError> {
+        self.pull(|next| search::decode_full(Search::new(lo, last, search::center(lo, last)), next))
+    }
+
+    /// Runs `decode` on the bits from the current position, advancing past
+    /// exactly the bits it pulled.
+    fn pull(
+        &mut self,
+        decode: impl FnOnce(&mut dyn FnMut() -> Option<bool>) -> Option<u128>,
+    ) -> Result<u128, BbseError> {
+        let mut bits = self.bits.as_bitslice()[self.pos..].iter().by_vals();
+        let mut used = 0;
+        let key = decode(&mut || {
+            used += 1;
+            bits.next()
+        })
+        .ok_or(BbseError::UnexpectedEnd { position: self.pos })?;
+        self.pos += used;
+        Ok(key)
+    }
+
+    /// Bit offset of the next value.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Number of bits not yet read.
+    pub fn remaining(&self) -> usize {
+        self.bits.len() - self.pos
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+}
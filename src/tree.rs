@@ -0,0 +1,299 @@
+//! Split trees fitted to a known distribution of values.
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+use core::convert::Infallible;
+
+use crate::format::{self, Source};
+use crate::{BbseError, BbseInt, BbsePath, Center, MidpointStrategy};
+
+const MAGIC: &[u8; 4] = b"BBST";
+const VERSION: u8 = 1;
+
+/// One search state `[lo, hi]` and its midpoint, as offsets from `start`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Node {
+    lo: usize,
+    hi: usize,
+    mid: usize,
+}
+
+/// The split tree with the fewest expected bits for a histogram of `[start, end)`
+///
+/// Every search state gets the midpoint that minimizes the total path length
+/// `Σ counts[v] * len(path(v))`, taking BBSE's early stop into account: a
+/// midpoint costs no further bits. Use the tree as a [`MidpointStrategy`] or
+/// through its own [`encode`](SplitTree::encode) and [`decode`](SplitTree::decode).
+///
+/// Values with a count of zero may end up very deep; add one to every count to
+/// keep unseen values within reach.
+///
+/// ```rust
+/// use bbse::{encode, SplitTree};
+/// // Residuals concentrated around zero.
+/// let counts: Vec<u64> = (-8i8..8).map(|v| 1 + (1000 >> (2 * v.unsigned_abs()))).collect();
+/// let tree = SplitTree::new(-8i8, 8, &counts);
+///
+/// assert!(tree.encode(0).is_empty());
+/// assert!(tree.encode(1).len() < encode(-8i8, 8, 1).len());
+/// assert_eq!(tree.decode(&tree.encode(-5)), -5);
+///
+/// let bytes = tree.to_bytes();
+/// assert_eq!(SplitTree::<i8>::from_bytes(&bytes), Ok(tree));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitTree<T> {
+    start: T,
+    end: T,
+    /// Sorted by `(lo, hi)`.
+    nodes: Vec<Node>,
+}
+
+impl<T: BbseInt> SplitTree<T> {
+    /// Builds the optimal tree for `counts`, one count per value of `[start, end)`.
+    ///
+    /// Takes `O(n²)` time and memory for `n` values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is empty or `counts` does not match its size.
+    /// See [`try_new`](SplitTree::try_new).
+    pub fn new(start: T, end: T, counts: &[u64]) -> Self {
+        Self::try_new(start, end, counts).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of [`new`](SplitTree::new)
+    pub fn try_new(start: T, end: T, counts: &[u64]) -> Result<Self, BbseError> {
+        crate::check_range(start, end)?;
+        let size = end.to_key() - start.to_key();
+        if counts.len() as u128 != size {
+            return Err(BbseError::HistogramMismatch {
+                len: counts.len(),
+                expected: size,
+            });
+        }
+
+        let roots = optimal_roots(counts);
+        let mut tree = Self {
+            start,
+            end,
+            nodes: Vec::new(),
+        };
+        let mut nodes = Vec::new();
+        tree.walk(|lo, hi| {
+            // Only `start` can still be the target without having been a midpoint.
+            let first = if lo == 0 { 0 } else { lo + 1 };
+            let mid = roots[triangle(first, hi)];
+            nodes.push(Node { lo, hi, mid });
+            Ok::<_, Infallible>(mid)
+        })
+        .unwrap_or_else(|e| match e {});
+
+        nodes.sort_unstable_by_key(|n| (n.lo, n.hi));
+        tree.nodes = nodes;
+        Ok(tree)
+    }
+
+    pub fn start(&self) -> T {
+        self.start
+    }
+
+    pub fn end(&self) -> T {
+        self.end
+    }
+
+    /// [`encode_with`](crate::encode_with) using this tree.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `target` lies outside `[start, end)`.
+    pub fn encode(&self, target: T) -> BbsePath {
+        crate::encode_with(self.start, self.end, target, self)
+    }
+
+    /// Fallible version of [`encode`](SplitTree::encode)
+    pub fn try_encode(&self, target: T) -> Result<BbsePath, BbseError> {
+        crate::try_encode_with(self.start, self.end, target, self)
+    }
+
+    /// [`decode_with`](crate::decode_with) using this tree; the path is not validated.
+    pub fn decode(&self, path: &BbsePath) -> T {
+        crate::decode_with(self.start, self.end, path, self)
+    }
+
+    /// Checked version of [`decode`](SplitTree::decode)
+    pub fn try_decode(&self, path: &BbsePath) -> Result<T, BbseError> {
+        crate::try_decode_with(self.start, self.end, path, self)
+    }
+
+    /// Serializes the tree so a decoder can rebuild it with [`from_bytes`](SplitTree::from_bytes).
+    ///
+    /// The layout is the magic `b"BBST"`, a version byte, the integer type,
+    /// `start` and `end` as in the [`format`](crate::format) module, followed by
+    /// the midpoint of every search state in pre-order, each written as the
+    /// LEB128 varint `mid - lo - 1`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        out.push(format::type_tag::<T>());
+        format::put_value(&mut out, self.start);
+        format::put_value(&mut out, self.end);
+
+        self.walk(|lo, hi| {
+            let mid = self
+                .node(lo, hi)
+                .expect("every search state has a node")
+                .mid;
+            format::put_varint(&mut out, (mid - lo - 1) as u64);
+            Ok::<_, Infallible>(mid)
+        })
+        .unwrap_or_else(|e| match e {});
+        out
+    }
+
+    /// Parses a tree written by [`to_bytes`](SplitTree::to_bytes).
+    ///
+    /// `T` must match the integer type the tree was built for.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BbseError> {
+        let mut source = bytes;
+        let mut magic = [0; 4];
+        source.fill(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(BbseError::InvalidFormat {
+                reason: "missing BBST magic",
+            });
+        }
+        let version = source.byte()?;
+        if version != VERSION {
+            return Err(BbseError::UnsupportedVersion { version });
+        }
+        if source.byte()? != format::type_tag::<T>() {
+            return Err(BbseError::InvalidFormat {
+                reason: "integer type does not match",
+            });
+        }
+        let start: T = format::get_value(&mut source)?;
+        let end: T = format::get_value(&mut source)?;
+        crate::check_range(start, end)?;
+        usize::try_from(end.to_key() - start.to_key()).map_err(|_| format::TOO_LARGE)?;
+
+        let mut tree = Self {
+            start,
+            end,
+            nodes: Vec::new(),
+        };
+        let mut nodes = Vec::new();
+        tree.walk(|lo, hi| {
+            let offset = format::get_varint(&mut source)?;
+            let mid = usize::try_from(offset)
+                .ok()
+                .filter(|&offset| offset < hi - lo)
+                .map(|offset| lo + 1 + offset)
+                .ok_or(BbseError::InvalidFormat {
+                    reason: "midpoint outside its search state",
+                })?;
+            nodes.push(Node { lo, hi, mid });
+            Ok(mid)
+        })?;
+        if !source.is_empty() {
+            return Err(BbseError::InvalidFormat {
+                reason: "trailing bytes",
+            });
+        }
+
+        nodes.sort_unstable_by_key(|n| (n.lo, n.hi));
+        tree.nodes = nodes;
+        Ok(tree)
+    }
+
+    /// Visits every search state `[lo, hi]` in pre-order; `visit` returns its midpoint.
+    fn walk<E>(&self, mut visit: impl FnMut(usize, usize) -> Result<usize, E>) -> Result<(), E> {
+        let size = (self.end.to_key() - self.start.to_key()) as usize;
+        let mut states = vec![(0, size - 1)];
+        while let Some((lo, hi)) = states.pop() {
+            if lo == hi {
+                continue;
+            }
+            let mid = visit(lo, hi)?;
+            states.push((mid, hi));
+            states.push((lo, mid - 1));
+        }
+        Ok(())
+    }
+
+    fn node(&self, lo: usize, hi: usize) -> Option<&Node> {
+        let i = self
+            .nodes
+            .binary_search_by_key(&(lo, hi), |n| (n.lo, n.hi))
+            .ok()?;
+        Some(&self.nodes[i])
+    }
+}
+
+/// Falls back to [`Center`] for states outside the tree, e.g. another range.
+impl<T: BbseInt> MidpointStrategy<T> for SplitTree<T> {
+    fn split(&self, lo: T, hi: T, depth: usize) -> T {
+        let base = self.start.to_key();
+        let offset = |v: T| {
+            v.to_key()
+                .checked_sub(base)
+                .and_then(|k| usize::try_from(k).ok())
+        };
+        match (offset(lo), offset(hi)) {
+            (Some(l), Some(h)) => match self.node(l, h) {
+                Some(node) => T::from_key(base + node.mid as u128),
+                None => Center.split(lo, hi, depth),
+            },
+            _ => Center.split(lo, hi, depth),
+        }
+    }
+}
+
+/// Index of the interval `[i, j]`, `i <= j`, in a flattened triangle.
+fn triangle(i: usize, j: usize) -> usize {
+    j * (j + 1) / 2 + i
+}
+
+/// Optimal midpoint of every interval `[i, j]` of candidate values.
+///
+/// This is the optimal binary search tree problem, solved with Knuth's bound
+/// `root[i][j - 1] <= root[i][j] <= root[i + 1][j]`. The one twist is that
+/// `start` (candidate `0`) is never a midpoint: the search reaches it by
+/// narrowing the range down to it alone.
+fn optimal_roots(counts: &[u64]) -> Vec<usize> {
+    let n = counts.len();
+    let mut prefix = vec![0u128; n + 1];
+    for (i, &c) in counts.iter().enumerate() {
+        prefix[i + 1] = prefix[i] + u128::from(c);
+    }
+
+    // Cost of a subtree counts the bit leading into it, so it includes its weight.
+    let mut cost = vec![0u128; triangle(0, n)];
+    let mut root = vec![0usize; triangle(0, n)];
+    for i in 0..n {
+        cost[triangle(i, i)] = u128::from(counts[i]);
+        root[triangle(i, i)] = i;
+    }
+    for len in 2..=n {
+        for i in 0..=n - len {
+            let j = i + len - 1;
+            let first = root[triangle(i, j - 1)].max(if i == 0 { 1 } else { i });
+            let last = root[triangle(i + 1, j)].max(first);
+
+            let (mut best, mut best_root) = (u128::MAX, first);
+            for r in first..=last {
+                let left = if r > i { cost[triangle(i, r - 1)] } else { 0 };
+                let right = if r < j { cost[triangle(r + 1, j)] } else { 0 };
+                if left + right < best {
+                    best = left + right;
+                    best_root = r;
+                }
+            }
+            cost[triangle(i, j)] = best + prefix[j + 1] - prefix[i];
+            root[triangle(i, j)] = best_root;
+        }
+    }
+    root
+}
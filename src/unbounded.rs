@@ -0,0 +1,91 @@
+//! Self-delimiting codes for non-negative values without an upper bound.
+
+use crate::search;
+use crate::{BbseError, BbseInt, BbsePath};
+
+/// Key of zero in `T`.
+fn zero<T: BbseInt>() -> u128 {
+    if T::SIGNED {
+        1 << (T::BITS - 1)
+    } else {
+        0
+    }
+}
+
+/// Distance of `value` from zero, or an error if it is negative.
+pub(crate) fn offset<T: BbseInt>(value: T) -> Result<u128, BbseError> {
+    let key = crate::check_range_target(zero::<T>(), T::MAX.to_key(), value)?;
+    Ok(key - zero::<T>())
+}
+
+/// Largest offset, that of `T::MAX`.
+pub(crate) fn max_offset<T: BbseInt>() -> u128 {
+    T::MAX.to_key() - zero::<T>()
+}
+
+pub(crate) fn from_offset<T: BbseInt>(offset: u128) -> T {
+    T::from_key(zero::<T>() + offset)
+}
+
+/// Self-delimiting code for a non-negative `value`
+///
+/// The search gallops first: one `1` for every bucket `[2^k - 1, 2^(k+1) - 1)`
+/// the value lies beyond, then a `0`. It then finds the value within its bucket
+/// of `2^k` values with the prefix-free [`BbseWriter`](crate::BbseWriter) search.
+/// A value `v` costs `2·floor(log2(v + 1)) + 1` bits, like Elias gamma:
+///
+/// | Value  | Code      |
+/// |--------|-----------|
+/// | 0      | `0`       |
+/// | 1, 2   | `10x`     |
+/// | 3..=6  | `110xx`   |
+/// | 7..=14 | `1110xxx` |
+///
+/// The type's maximum bounds the last bucket, which needs no stop bit.
+///
+/// ```rust
+/// use bbse::{decode_unbounded, encode_unbounded};
+/// assert_eq!(encode_unbounded(0u64), "0");
+/// assert_eq!(encode_unbounded(5u64), "11010");
+/// assert_eq!(decode_unbounded::<u64>(&encode_unbounded(1_000_000u64)), 1_000_000);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `value` is negative. See [`try_encode_unbounded`].
+pub fn encode_unbounded<T: BbseInt>(value: T) -> BbsePath {
+    try_encode_unbounded(value).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Fallible version of [`encode_unbounded`]
+pub fn try_encode_unbounded<T: BbseInt>(value: T) -> Result<BbsePath, BbseError> {
+    let mut path = BbsePath::new();
+    search::encode_unbounded(offset(value)?, max_offset::<T>(), |bit| path.push(bit));
+    Ok(path)
+}
+
+/// Decodes a path from [`encode_unbounded`]; bits after the code are ignored.
+///
+/// # Panics
+///
+/// Panics if the path ends inside the code. See [`try_decode_unbounded`].
+pub fn decode_unbounded<T: BbseInt>(path: &BbsePath) -> T {
+    let mut bits = path.iter();
+    let offset = search::decode_unbounded(max_offset::<T>(), || bits.next())
+        .unwrap_or_else(|| panic!("{}", BbseError::UnexpectedEnd { position: 0 }));
+    from_offset(offset)
+}
+
+/// Checked version of [`decode_unbounded`]: the path must hold exactly one code.
+pub fn try_decode_unbounded<T: BbseInt>(path: &BbsePath) -> Result<T, BbseError> {
+    let mut bits = path.iter();
+    let offset = search::decode_unbounded(max_offset::<T>(), || bits.next())
+        .ok_or(BbseError::UnexpectedEnd { position: 0 })?;
+    if bits.len() > 0 {
+        return Err(BbseError::PathTooLong {
+            len: path.len(),
+            max: path.len() - bits.len(),
+        });
+    }
+    Ok(from_offset(offset))
+}
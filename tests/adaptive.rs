@@ -0,0 +1,76 @@
+#![cfg(feature = "alloc")]
+
+use bbse::{encode, AdaptiveCoder, BbseError, BbsePath};
+use proptest::prelude::*;
+
+#[test]
+fn test_follows_drift() {
+    // A slow ramp far from the center of the range.
+    let readings: Vec<u32> = (0..200).map(|i| 40_000 + i * 3 + i % 7).collect();
+
+    let mut encoder = AdaptiveCoder::new(0u32, 65_536).with_shift(1);
+    let adaptive: usize = readings.iter().map(|&v| encoder.encode(v).len()).sum();
+    let fixed: usize = readings
+        .iter()
+        .map(|&v| encode(0u32, 65_536, v).len())
+        .sum();
+    assert!(adaptive < fixed / 2, "{} vs {}", adaptive, fixed);
+}
+
+#[test]
+fn test_constant_input_converges() {
+    let mut coder = AdaptiveCoder::new(-100i8, 100).with_shift(3);
+    let lengths: Vec<usize> = (0..40).map(|_| coder.encode(-90).len()).collect();
+    assert_eq!(coder.midpoint(), -90);
+    assert_eq!(lengths.last(), Some(&0));
+}
+
+#[test]
+fn test_midpoint_stays_inside() {
+    let mut coder = AdaptiveCoder::new(0u8, 10).with_shift(0);
+    coder.encode(0);
+    assert_eq!(coder.midpoint(), 1);
+    coder.encode(9);
+    assert_eq!(coder.midpoint(), 9);
+
+    let mut pair = AdaptiveCoder::new(5u8, 7);
+    assert_eq!(pair.midpoint(), 6);
+    assert_eq!(pair.encode(5), "0");
+
+    let mut single = AdaptiveCoder::new(u8::MAX - 1, u8::MAX);
+    assert_eq!(single.encode(u8::MAX - 1), BbsePath::new());
+    assert_eq!(single.decode(&BbsePath::new()), u8::MAX - 1);
+}
+
+#[test]
+fn test_errors_do_not_update() {
+    let mut coder = AdaptiveCoder::new(0u16, 100);
+    let before = coder.clone();
+    assert_eq!(
+        coder.try_encode(100),
+        Err(BbseError::TargetOutOfBounds {
+            target: 100u16.into(),
+            start: 0u16.into(),
+            end: 100u16.into(),
+        })
+    );
+    assert!(coder.try_decode(&"1111111111".parse().unwrap()).is_err());
+    assert_eq!(coder, before);
+    assert!(AdaptiveCoder::try_new(3u16, 3).is_err());
+}
+
+proptest! {
+    #[test]
+    fn encoder_and_decoder_stay_in_sync(
+        values in prop::collection::vec(-5000i64..5000, 0..100),
+        shift in 0u32..8,
+    ) {
+        let mut encoder = AdaptiveCoder::new(-5000i64, 5000).with_shift(shift);
+        let mut decoder = AdaptiveCoder::new(-5000i64, 5000).with_shift(shift);
+        for &v in &values {
+            let path = encoder.encode(v);
+            prop_assert_eq!(decoder.try_decode(&path), Ok(v));
+        }
+        prop_assert_eq!(encoder, decoder);
+    }
+}
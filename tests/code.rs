@@ -0,0 +1,102 @@
+//! The allocation-free `BbseCode` API, which builds without the `alloc` feature.
+
+use bbse::{
+    decode_const, decode_inline, encode_const, encode_inline, try_decode_inline, try_encode_inline,
+    BbseCode, BbseError,
+};
+use proptest::prelude::*;
+
+#[test]
+fn test_full_width_u128() {
+    for v in [0, 1, u128::MAX / 2, u128::MAX - 1] {
+        let code = encode_inline(0, u128::MAX, v);
+        assert!(code.len() <= BbseCode::CAPACITY);
+        assert_eq!(decode_inline(0, u128::MAX, code), v);
+    }
+    let deepest = encode_inline(0, u128::MAX, 0);
+    assert_eq!(deepest.len(), 127);
+    assert_eq!(deepest.bits(), 0);
+}
+
+#[test]
+fn test_code_accessors() {
+    let code: BbseCode = "1011".parse().unwrap();
+    assert_eq!((code.bits(), code.len()), (0b1011, 4));
+    assert_eq!(code.get(0), Some(true));
+    assert_eq!(code.get(1), Some(false));
+    assert_eq!(code.get(4), None);
+    assert_eq!(
+        code.iter().rev().collect::<Vec<_>>(),
+        [true, true, false, true]
+    );
+    assert_eq!(format!("{:?}", code), "BbseCode(\"1011\")");
+    assert_eq!(code.to_string(), "1011");
+    assert_eq!(BbseCode::from_bits(0b1011, 4), Some(code));
+    assert_eq!(BbseCode::from_bits(0b1011, 3), None);
+    assert_eq!(
+        BbseCode::from_bits(u128::MAX, 128).map(|c| c.len()),
+        Some(128)
+    );
+    assert_eq!(BbseCode::from_bits(0, 129), None);
+    assert!("10a".parse::<BbseCode>().is_err());
+}
+
+#[test]
+fn test_capacity() {
+    let mut code = BbseCode::from_bits(0, 128).unwrap();
+    assert_eq!(
+        code.push(true),
+        Err(BbseError::CapacityExceeded { capacity: 128 })
+    );
+}
+
+#[test]
+fn test_errors() {
+    assert!(try_encode_inline(3u8, 3, 3).is_err());
+    assert!(try_encode_inline(0u8, 3, 3).is_err());
+    assert!(try_decode_inline(3u8, 3, BbseCode::new()).is_err());
+}
+
+const PALETTE: [BbseCode; 256] = {
+    let mut codes = [BbseCode::new(); 256];
+    let mut i = 0;
+    while i < 256 {
+        codes[i] = encode_const(0, 256, i as u128);
+        i += 1;
+    }
+    codes
+};
+
+#[test]
+fn test_const_table() {
+    for v in 0..=255u8 {
+        assert_eq!(PALETTE[v as usize], encode_inline(0u16, 256, v as u16));
+        assert_eq!(decode_const(0, 256, PALETTE[v as usize]), v as u128);
+    }
+    const TOP: u128 = decode_const(0, u128::MAX, encode_const(0, u128::MAX, u128::MAX - 1));
+    assert_eq!(TOP, u128::MAX - 1);
+}
+
+#[test]
+fn test_const_signed_offsets() {
+    for v in -100i8..100 {
+        let offset = (v as i16 + 100) as u128;
+        assert_eq!(encode_const(0, 200, offset), encode_inline(-100i8, 100, v));
+    }
+}
+
+#[test]
+#[should_panic(expected = "target out of bounds")]
+fn test_const_out_of_bounds() {
+    encode_const(0, 8, 8);
+}
+
+proptest! {
+    #[test]
+    fn roundtrip_i128(a in any::<i128>(), b in any::<i128>(), v in any::<i128>()) {
+        let (start, end) = (a.min(b), a.max(b));
+        prop_assume!(start < end && start <= v && v < end);
+        let code = encode_inline(start, end, v);
+        prop_assert_eq!(try_decode_inline(start, end, code), Ok(v));
+    }
+}
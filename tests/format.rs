@@ -0,0 +1,126 @@
+#![cfg(feature = "std")]
+
+use bbse::{encode, encode_from, BBSEStack, BbseError, Midpoint, StackLayout};
+
+fn sample_stack() -> BBSEStack {
+    let mut stack = BBSEStack::new();
+    for v in [-100i16, -1, 0, 1, 57, 99] {
+        stack.push(encode(-100i16, 100, v));
+    }
+    stack
+}
+
+#[test]
+fn test_roundtrip_center() {
+    let stack = sample_stack();
+    let layout = StackLayout::new(-100i16, 100);
+    let bytes = stack.to_bytes(&layout).unwrap();
+
+    let (restored, restored_layout) = BBSEStack::from_bytes::<i16>(&bytes).unwrap();
+    assert_eq!(restored_layout, layout);
+    assert_eq!(restored.entries, stack.entries);
+    assert_eq!(
+        restored_layout.decode_all(&restored),
+        Ok(vec![-100, -1, 0, 1, 57, 99])
+    );
+}
+
+#[test]
+fn test_roundtrip_custom_midpoint() {
+    let mut stack = BBSEStack::new();
+    for v in 0..256u32 {
+        stack.push(encode_from(0u32, 256, v, 16));
+    }
+    let layout = StackLayout::with_midpoint(0u32, 256, 16);
+    let bytes = stack.to_bytes(&layout).unwrap();
+
+    let (restored, restored_layout) = BBSEStack::from_bytes::<u32>(&bytes).unwrap();
+    assert_eq!(restored_layout.midpoint, Midpoint::Root(16));
+    assert_eq!(
+        restored_layout.decode_all(&restored),
+        Ok((0..256).collect::<Vec<_>>())
+    );
+}
+
+#[test]
+fn test_header_layout() {
+    let mut stack = BBSEStack::new();
+    stack.push(encode(0u8, 8, 5));
+    let bytes = stack.to_bytes(&StackLayout::new(0u8, 8)).unwrap();
+    // magic, version, u8, center, start, end, one path of two bits "10"
+    assert_eq!(
+        bytes,
+        vec![b'B', b'B', b'S', b'E', 1, 1, 0, 0, 8, 1, 2, 0b1000_0000]
+    );
+
+    let bytes = BBSEStack::new()
+        .to_bytes(&StackLayout::new(-2i32, 5))
+        .unwrap();
+    assert_eq!(
+        &bytes[5..],
+        &[0x84, 0, 0xff, 0xff, 0xff, 0xfe, 0, 0, 0, 5, 0]
+    );
+}
+
+#[test]
+fn test_io_roundtrip() {
+    let stack = sample_stack();
+    let layout = StackLayout::new(-100i16, 100);
+    let mut buffer = Vec::new();
+    stack.write_to(&layout, &mut buffer).unwrap();
+    stack.write_to(&layout, &mut buffer).unwrap();
+
+    let mut reader = buffer.as_slice();
+    for _ in 0..2 {
+        let (restored, _) = BBSEStack::read_from::<i16, _>(&mut reader).unwrap();
+        assert_eq!(restored.entries, stack.entries);
+    }
+    assert!(reader.is_empty());
+}
+
+#[test]
+fn test_rejects_bad_input() {
+    let stack = sample_stack();
+    let layout = StackLayout::new(-100i16, 100);
+    let bytes = stack.to_bytes(&layout).unwrap();
+
+    assert!(matches!(
+        BBSEStack::from_bytes::<u16>(&bytes),
+        Err(BbseError::InvalidFormat { .. })
+    ));
+    assert!(matches!(
+        BBSEStack::from_bytes::<i16>(&bytes[..bytes.len() - 1]),
+        Err(BbseError::InvalidFormat { .. })
+    ));
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert!(BBSEStack::from_bytes::<i16>(&trailing).is_err());
+
+    // 128-bit widths must not collide with the signed flag.
+    let mut wide = BBSEStack::new();
+    wide.push(encode(0u128, 10, 3));
+    let wide = wide.to_bytes(&StackLayout::new(0u128, 10)).unwrap();
+    assert_eq!(
+        BBSEStack::from_bytes::<i128>(&wide).unwrap_err(),
+        BbseError::InvalidFormat {
+            reason: "integer type does not match"
+        }
+    );
+    assert!(BBSEStack::from_bytes::<u128>(&wide).is_ok());
+
+    let mut version = bytes.clone();
+    version[4] = 9;
+    assert_eq!(
+        BBSEStack::from_bytes::<i16>(&version).unwrap_err(),
+        BbseError::UnsupportedVersion { version: 9 }
+    );
+
+    let err = BBSEStack::read_from::<i16, _>(&bytes[..10]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+
+    // paths that do not belong to the recorded range are refused both ways
+    assert!(stack.to_bytes(&StackLayout::new(0i16, 2)).is_err());
+    assert!(stack
+        .to_bytes(&StackLayout::with_midpoint(-100i16, 100, 100))
+        .is_err());
+}
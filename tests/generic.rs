@@ -0,0 +1,107 @@
+#![cfg(feature = "alloc")]
+
+use bbse::{
+    decode, decode_from, encode, encode_from, try_decode, try_encode, BBSEStack, BbseError,
+    BbseInt, IntValue,
+};
+use proptest::prelude::*;
+
+#[test]
+fn test_signed_range_matches_shifted_unsigned() {
+    for v in -128i16..128 {
+        let signed = encode(-128i16, 128, v);
+        let unsigned = encode(0u16, 256, (v + 128) as u16);
+        assert_eq!(signed, unsigned, "value {}", v);
+        assert_eq!(decode(-128i16, 128, &signed), v);
+    }
+}
+
+#[test]
+fn test_i8_near_type_bounds() {
+    for v in i8::MIN..i8::MAX {
+        let path = encode(i8::MIN, i8::MAX, v);
+        assert_eq!(decode(i8::MIN, i8::MAX, &path), v);
+        assert_eq!(try_decode(i8::MIN, i8::MAX, &path), Ok(v));
+
+        let path = encode_from(i8::MIN, i8::MAX, v, 0);
+        assert_eq!(decode_from(i8::MIN, i8::MAX, &path, 0), v);
+    }
+}
+
+#[test]
+fn test_every_integer_type() {
+    fn roundtrip<T: BbseInt>(start: T, end: T, targets: &[T]) {
+        for &target in targets {
+            let path = encode(start, end, target);
+            assert_eq!(decode(start, end, &path), target);
+        }
+    }
+
+    roundtrip(0u8, 200, &[0, 1, 100, 199]);
+    roundtrip(1000u16, 60000, &[1000, 30000, 59999]);
+    roundtrip(0u32, u32::MAX, &[0, 12345, u32::MAX - 1]);
+    roundtrip(u64::MAX - 10, u64::MAX, &[u64::MAX - 10, u64::MAX - 1]);
+    roundtrip(0u128, u128::MAX, &[0, u128::MAX / 3, u128::MAX - 1]);
+    roundtrip(0usize, 17, &[0, 16]);
+    roundtrip(-300i16, 300, &[-300, -1, 0, 299]);
+    roundtrip(i32::MIN, i32::MAX, &[i32::MIN, -1, 0, i32::MAX - 1]);
+    roundtrip(i64::MIN, 0, &[i64::MIN, -1]);
+    roundtrip(i128::MIN, i128::MAX, &[i128::MIN, 0, i128::MAX - 1]);
+    roundtrip(-5isize, 5, &[-5, 0, 4]);
+}
+
+#[test]
+fn test_signed_errors() {
+    assert_eq!(
+        try_encode(-10i32, -20, -15),
+        Err(BbseError::EmptyRange {
+            start: (-10).into(),
+            end: (-20).into()
+        })
+    );
+    assert_eq!(
+        try_encode(-64i8, 64, 64).unwrap_err().to_string(),
+        "target (64) out of bounds [-64, 64)"
+    );
+}
+
+#[test]
+fn test_int_value() {
+    assert_eq!(IntValue::from(5u8), IntValue::from(5i64));
+    assert_ne!(IntValue::from(-5i8), IntValue::from(5u8));
+    assert_eq!(IntValue::from(i128::MIN).to_i128(), Some(i128::MIN));
+    assert_eq!(IntValue::from(u128::MAX).to_i128(), None);
+    assert_eq!(IntValue::from(-1i8).to_u128(), None);
+    assert_eq!(IntValue::from(-42i16).to_string(), "-42");
+}
+
+#[test]
+fn test_generic_stack() {
+    let mut stack = BBSEStack::new();
+    for v in [-3i16, -1, 0, 2, 5] {
+        stack.push(encode(-8i16, 8, v));
+    }
+    assert_eq!(stack.decode_all(-8i16, 8), vec![-3, -1, 0, 2, 5]);
+}
+
+proptest! {
+    #[test]
+    fn roundtrip_i64(a in any::<i64>(), b in any::<i64>(), seed in any::<u64>()) {
+        let (start, end) = (a.min(b), a.max(b));
+        prop_assume!(start < end);
+        let span = (end as i128 - start as i128) as u128;
+        let target = (start as i128 + (seed as u128 % span) as i128) as i64;
+        let path = encode(start, end, target);
+        prop_assert_eq!(try_decode(start, end, &path), Ok(target));
+    }
+
+    #[test]
+    fn roundtrip_u128(a in any::<u128>(), b in any::<u128>(), seed in any::<u128>()) {
+        let (start, end) = (a.min(b), a.max(b));
+        prop_assume!(start < end);
+        let target = start + seed % (end - start);
+        let path = encode(start, end, target);
+        prop_assert!(path.len() <= 128);
+        prop_assert_eq!(try_decode(start, end, &path), Ok(target));
+    }
+}
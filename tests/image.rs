@@ -0,0 +1,269 @@
+#![cfg(feature = "alloc")]
+
+use bbse::image::{ColorType, Image, Predictor};
+use bbse::BbseError;
+use proptest::prelude::*;
+
+fn checkerboard(size: u32, color: ColorType) -> Image {
+    let pixels = (0..size * size)
+        .flat_map(|i| {
+            let v = if (i % size + i / size) % 2 == 0 {
+                0
+            } else {
+                255
+            };
+            vec![v; color.channels()]
+        })
+        .collect();
+    Image::new(size, size, color, pixels).unwrap()
+}
+
+#[test]
+fn test_flat_image_costs_a_bit_per_channel() {
+    let image = Image::new(10, 10, ColorType::Rgba8, vec![0; 400]).unwrap();
+    let bytes = image.encode();
+    // 13 header bytes, then 400 one-bit residuals.
+    assert_eq!(bytes.len(), 13 + 50);
+    assert_eq!(Image::decode(&bytes), Ok(image));
+}
+
+#[test]
+fn test_extreme_residuals() {
+    for color in [ColorType::Rgb8, ColorType::Rgba8] {
+        let image = checkerboard(9, color);
+        for midpoint in [-254, -100, 0, 1, 255] {
+            let bytes = image.encode_with_midpoint(midpoint).unwrap();
+            assert_eq!(Image::decode(&bytes).as_ref(), Ok(&image));
+        }
+    }
+}
+
+#[test]
+fn test_midpoint() {
+    // Every residual after the first pixel is +3.
+    let pixels: Vec<u8> = (0..64).flat_map(|i| [i * 3, i * 3, i * 3]).collect();
+    let image = Image::new(64, 1, ColorType::Rgb8, pixels).unwrap();
+    let centered = image.encode();
+    let tuned = image.encode_with_midpoint(3).unwrap();
+    assert!(tuned.len() * 3 < centered.len());
+    assert_eq!(Image::decode(&tuned), Ok(image.clone()));
+
+    assert_eq!(
+        image.encode_with_midpoint(-255),
+        Err(BbseError::InvalidMidpoint {
+            midpoint: (-255).into(),
+            start: (-255).into(),
+            end: 256.into(),
+        })
+    );
+    assert!(image.encode_with_midpoint(256).is_err());
+}
+
+#[test]
+fn test_empty_images() {
+    for (w, h) in [(0, 0), (0, 7), (7, 0)] {
+        let image = Image::new(w, h, ColorType::Rgb8, vec![]).unwrap();
+        let bytes = image.encode();
+        assert_eq!(Image::decode(&bytes), Ok(image));
+    }
+}
+
+#[test]
+fn test_buffer_mismatch() {
+    assert_eq!(
+        Image::new(2, 2, ColorType::Rgba8, vec![0; 12]),
+        Err(BbseError::PixelBufferMismatch {
+            len: 12,
+            expected: 16
+        })
+    );
+    let image = Image::new(2, 2, ColorType::Rgb8, (0..12).collect()).unwrap();
+    assert_eq!(image.width(), 2);
+    assert_eq!(image.height(), 2);
+    assert_eq!(image.color(), ColorType::Rgb8);
+    assert_eq!(image.into_pixels(), (0..12).collect::<Vec<u8>>());
+}
+
+#[test]
+fn test_decode_errors() {
+    let image = checkerboard(4, ColorType::Rgb8);
+    let bytes = image.encode();
+    let invalid = |reason| Err(BbseError::InvalidFormat { reason });
+
+    assert_eq!(
+        Image::decode(&bytes[..bytes.len() - 1]),
+        invalid("truncated data")
+    );
+    let mut longer = bytes.clone();
+    longer.push(0);
+    assert_eq!(Image::decode(&longer), invalid("trailing bytes"));
+
+    let mut bad = bytes.clone();
+    bad[0] = b'X';
+    assert_eq!(Image::decode(&bad), invalid("missing BBSI magic"));
+    let mut bad = bytes.clone();
+    bad[4] = 9;
+    assert_eq!(
+        Image::decode(&bad),
+        Err(BbseError::UnsupportedVersion { version: 9 })
+    );
+    let mut bad = bytes.clone();
+    bad[5] = 2;
+    assert_eq!(Image::decode(&bad), invalid("unknown colour type"));
+    let mut bad = bytes.clone();
+    bad[5] = 0x80 | 17;
+    assert_eq!(
+        Image::decode(&bad),
+        invalid("bit depth must lie within 1..=16")
+    );
+    let mut bad = bytes.clone();
+    bad[6] = 4;
+    assert_eq!(Image::decode(&bad), invalid("unknown predictor"));
+
+    // A flat image leaves seven padding bits in its last byte.
+    let flat = Image::new(3, 3, ColorType::Rgb8, vec![0; 27]).unwrap();
+    let mut bad = flat.encode();
+    *bad.last_mut().unwrap() |= 1;
+    assert_eq!(Image::decode(&bad), invalid("non-zero padding"));
+
+    // Residuals 255 cost 3 × 16 bits, then the 0s 3 × 1. Turning the first 0
+    // into +1 (`100`) decodes to 256.
+    let bright = Image::new(2, 1, ColorType::Rgb8, vec![255; 6]).unwrap();
+    let mut bad = bright.encode();
+    assert_eq!(bad.len(), 13 + 7);
+    bad[13 + 6] |= 0x80; // bit 48
+    assert_eq!(Image::decode(&bad), invalid("pixel value out of range"));
+}
+
+#[test]
+fn test_gray_depths() {
+    // A one-bit mask: a filled square on an empty background.
+    let mask: Vec<u8> = (0..16 * 16)
+        .map(|i| u8::from((4..12).contains(&(i % 16)) && (4..12).contains(&(i / 16))))
+        .collect();
+    let image = Image::new(16, 16, ColorType::Gray(1), mask).unwrap();
+    let bytes = image.encode();
+    // One bit per sample, plus a few for the edges of the square.
+    assert_eq!(bytes.len(), 13 + 34);
+    assert_eq!(Image::decode(&bytes), Ok(image));
+
+    // A 16-bit depth map, stored big-endian.
+    let depths: Vec<u8> = (0..8u16 * 8)
+        .flat_map(|i| (1000 + 300 * (i % 8) + 7 * (i / 8)).to_be_bytes())
+        .collect();
+    let image = Image::new(8, 8, ColorType::Gray(16), depths).unwrap();
+    assert_eq!(image.color().bytes_per_sample(), 2);
+    for midpoint in [-65534, 0, 300, 65535] {
+        let bytes = image.encode_with_midpoint(midpoint).unwrap();
+        assert_eq!(Image::decode(&bytes).as_ref(), Ok(&image));
+    }
+    assert!(image.encode_with_midpoint(65536).is_err());
+}
+
+#[test]
+fn test_gray_errors() {
+    assert_eq!(
+        Image::new(1, 1, ColorType::Gray(0), vec![0]),
+        Err(BbseError::InvalidFormat {
+            reason: "bit depth must lie within 1..=16"
+        })
+    );
+    assert_eq!(
+        Image::new(2, 1, ColorType::Gray(4), vec![15, 16]),
+        Err(BbseError::TargetOutOfBounds {
+            target: 16.into(),
+            start: 0.into(),
+            end: 16.into(),
+        })
+    );
+    assert_eq!(
+        Image::new(2, 1, ColorType::Gray(12), vec![0; 2]),
+        Err(BbseError::PixelBufferMismatch {
+            len: 2,
+            expected: 4
+        })
+    );
+    assert!(Image::new(1, 1, ColorType::Gray(12), vec![0x10, 0]).is_err());
+
+    // Residuals of a 2-bit image lie in [-3, 4).
+    let image = Image::new(1, 1, ColorType::Gray(2), vec![3]).unwrap();
+    let bytes = image.encode_with_midpoint(3).unwrap();
+    assert_eq!(Image::decode(&bytes), Ok(image.clone()));
+    assert!(image.encode_with_midpoint(-3).is_err());
+    assert!(image.encode_with_midpoint(4).is_err());
+}
+
+#[test]
+fn test_predictors() {
+    // Horizontal stripes favour `Left`, vertical ones `Up`.
+    let stripes = |vertical: bool| {
+        let pixels = (0..32 * 32)
+            .map(|i: u32| {
+                let line = if vertical { i % 32 } else { i / 32 };
+                (line * 37 % 256) as u8
+            })
+            .collect();
+        Image::new(32, 32, ColorType::Gray(8), pixels).unwrap()
+    };
+    let size = |image: &Image, predictor| image.encode_with(predictor, 0).unwrap().len();
+
+    let horizontal = stripes(false);
+    assert!(size(&horizontal, Predictor::Left) < size(&horizontal, Predictor::Up));
+    let vertical = stripes(true);
+    assert!(size(&vertical, Predictor::Up) < size(&vertical, Predictor::Left));
+    assert!(size(&vertical, Predictor::Paeth) < size(&vertical, Predictor::Average));
+
+    for image in [horizontal, vertical, checkerboard(7, ColorType::Rgba8)] {
+        for predictor in [
+            Predictor::Left,
+            Predictor::Up,
+            Predictor::Average,
+            Predictor::Paeth,
+        ] {
+            let bytes = image.encode_with(predictor, 0).unwrap();
+            assert_eq!(Image::decode(&bytes).as_ref(), Ok(&image));
+        }
+    }
+    assert_eq!(Predictor::default(), Predictor::Average);
+}
+
+proptest! {
+    #[test]
+    fn roundtrip(
+        (w, h, rgba, pixels) in (0u32..12, 0u32..12, any::<bool>()).prop_flat_map(|(w, h, rgba)| {
+            let len = (w * h) as usize * if rgba { 4 } else { 3 };
+            (Just(w), Just(h), Just(rgba), prop::collection::vec(any::<u8>(), len))
+        }),
+        midpoint in -254i32..256,
+    ) {
+        let color = if rgba { ColorType::Rgba8 } else { ColorType::Rgb8 };
+        let image = Image::new(w, h, color, pixels).unwrap();
+        let bytes = image.encode_with_midpoint(midpoint).unwrap();
+        prop_assert_eq!(Image::decode(&bytes), Ok(image));
+    }
+}
+
+proptest! {
+    #[test]
+    fn gray_roundtrip(
+        (w, h, depth, samples) in (0u32..10, 0u32..10, 1u8..=16).prop_flat_map(|(w, h, depth)| {
+            let len = (w * h) as usize;
+            (Just(w), Just(h), Just(depth), prop::collection::vec(0u32..1 << depth, len))
+        }),
+        predictor in prop::sample::select(vec![
+            Predictor::Left,
+            Predictor::Up,
+            Predictor::Average,
+            Predictor::Paeth,
+        ]),
+    ) {
+        let pixels = if depth > 8 {
+            samples.iter().flat_map(|&v| (v as u16).to_be_bytes()).collect()
+        } else {
+            samples.iter().map(|&v| v as u8).collect()
+        };
+        let image = Image::new(w, h, ColorType::Gray(depth), pixels).unwrap();
+        let bytes = image.encode_with(predictor, 0).unwrap();
+        prop_assert_eq!(Image::decode(&bytes), Ok(image));
+    }
+}
@@ -0,0 +1,87 @@
+#![cfg(feature = "alloc")]
+
+use bbse::{BbseError, IndexedSeq, PackedStack};
+use proptest::prelude::*;
+
+#[test]
+fn test_get() {
+    let mut seq = IndexedSeq::new(-500i32, 500);
+    let values: Vec<i32> = (0..1000).map(|i| (i * 37) % 1000 - 500).collect();
+    for &v in &values {
+        seq.push(v);
+    }
+    assert_eq!(seq.rate(), 32);
+    assert_eq!(seq.sample_count(), 32);
+    for (i, &v) in values.iter().enumerate() {
+        assert_eq!(seq.get(i), Some(v));
+    }
+    assert_eq!(seq.get(values.len()), None);
+    assert_eq!(seq.decode_all(), values);
+}
+
+#[test]
+fn test_same_bits_as_stack() {
+    let mut seq = IndexedSeq::new(0u8, 100);
+    let mut stack = PackedStack::new(0u8, 100);
+    for v in [3, 50, 99, 0, 7] {
+        seq.push(v);
+        stack.push(v);
+    }
+    assert_eq!(seq.bit_len(), stack.bit_len());
+}
+
+#[test]
+fn test_resample() {
+    let mut seq = IndexedSeq::new(0u64, 1 << 40);
+    for v in 0..50 {
+        seq.push(v << 30);
+    }
+    let seq = seq.with_rate(1);
+    assert_eq!(seq.sample_count(), 50);
+    let mut seq = seq.with_rate(16);
+    assert_eq!(seq.sample_count(), 4);
+    assert_eq!(seq.get(49), Some(49 << 30));
+    seq.push(1);
+    assert_eq!(seq.get(50), Some(1));
+
+    seq.clear();
+    assert!(seq.is_empty());
+    assert_eq!(seq.sample_count(), 0);
+    assert_eq!(seq.get(0), None);
+}
+
+#[test]
+#[should_panic(expected = "sampling rate must be positive")]
+fn test_zero_rate() {
+    let _ = IndexedSeq::new(0u8, 10).with_rate(0);
+}
+
+#[test]
+fn test_errors() {
+    let mut seq = IndexedSeq::new(0u8, 10);
+    assert_eq!(
+        seq.try_push(10),
+        Err(BbseError::TargetOutOfBounds {
+            target: 10u8.into(),
+            start: 0u8.into(),
+            end: 10u8.into(),
+        })
+    );
+    assert!(seq.is_empty());
+    assert!(IndexedSeq::try_new(5u8, 5).is_err());
+}
+
+proptest! {
+    #[test]
+    fn random_access(values in prop::collection::vec(any::<u16>(), 0..300), rate in 1usize..40) {
+        let mut seq = IndexedSeq::new(0u16, u16::MAX).with_rate(rate);
+        let values: Vec<u16> = values.into_iter().map(|v| v.min(u16::MAX - 1)).collect();
+        for &v in &values {
+            seq.push(v);
+        }
+        prop_assert_eq!(seq.sample_count(), values.len().div_ceil(rate));
+        for (i, &v) in values.iter().enumerate() {
+            prop_assert_eq!(seq.get(i), Some(v));
+        }
+    }
+}
@@ -0,0 +1,33 @@
+#![cfg(feature = "alloc")]
+
+use bbse::{
+    decode_inline, encode, encode_inline, try_decode, try_decode_inline, BbseCode, BbsePath,
+};
+
+#[test]
+fn test_matches_heap_paths() {
+    for v in -200i16..200 {
+        let code = encode_inline(-200i16, 200, v);
+        let path = encode(-200i16, 200, v);
+        assert_eq!(BbsePath::from(code), path);
+        assert_eq!(BbseCode::try_from(&path), Ok(code));
+        assert_eq!(code.to_string(), path.to_string());
+        assert_eq!(decode_inline(-200i16, 200, code), v);
+        assert_eq!(try_decode_inline(-200i16, 200, code), Ok(v));
+    }
+}
+
+#[test]
+fn test_path_capacity() {
+    let long: BbsePath = std::iter::repeat(true).take(129).collect();
+    assert!(BbseCode::try_from(&long).is_err());
+}
+
+#[test]
+fn test_errors_match_heap_paths() {
+    let code: BbseCode = "1".parse().unwrap();
+    assert_eq!(
+        try_decode_inline(0u8, 2, code),
+        try_decode(0u8, 2, &"1".parse().unwrap())
+    );
+}
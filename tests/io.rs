@@ -0,0 +1,159 @@
+#![cfg(feature = "std")]
+
+use std::io::{self, Read};
+
+use bbse::{BbseError, BbseStreamReader, BbseStreamWriter, BbseWriter};
+use proptest::prelude::*;
+
+/// Hands out one byte per `read` call.
+struct Trickle<'a>(&'a [u8]);
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.0.split_first() {
+            Some((&b, rest)) if !buf.is_empty() => {
+                buf[0] = b;
+                self.0 = rest;
+                Ok(1)
+            }
+            _ => Ok(0),
+        }
+    }
+}
+
+fn bbse_error(e: &io::Error) -> Option<&BbseError> {
+    e.get_ref()?.downcast_ref()
+}
+
+#[test]
+fn test_same_bits_as_writer() {
+    let mut stream = BbseStreamWriter::new(Vec::new());
+    let mut writer = BbseWriter::new();
+    for v in [3u16, 999, 0, 512] {
+        stream.write_value(0, 1000, v).unwrap();
+        writer.write(0, 1000, v).unwrap();
+    }
+    assert_eq!(stream.bit_len(), 39);
+    let bytes = stream.finish().unwrap();
+
+    let mut expected = writer.into_path();
+    expected.push(true);
+    while expected.len() % 8 != 0 {
+        expected.push(false);
+    }
+    assert_eq!(bytes, expected.as_raw_slice());
+}
+
+#[test]
+fn test_padding() {
+    // Aligned data gets a whole byte of padding.
+    let mut writer = BbseStreamWriter::new(Vec::new());
+    writer.write_value(0u16, 256, 7).unwrap();
+    assert_eq!(writer.bit_len(), 8);
+    assert_eq!(writer.finish().unwrap(), [7, 0b1000_0000]);
+
+    let empty = BbseStreamWriter::new(Vec::new()).finish().unwrap();
+    assert_eq!(empty, [0x80]);
+    let mut reader = BbseStreamReader::new(&empty[..]);
+    assert!(reader.is_at_end().unwrap());
+    // A single-value range needs no bits, even at the end.
+    assert_eq!(reader.read_value(5u8, 6).unwrap(), 5);
+}
+
+#[test]
+fn test_empty_input() {
+    // `finish` always writes the marker byte, so no bytes at all is a truncated stream.
+    let mut reader = BbseStreamReader::new(&[][..]);
+    let err = reader.is_at_end().unwrap_err();
+    assert_eq!(
+        bbse_error(&err),
+        Some(&BbseError::InvalidFormat {
+            reason: "missing padding marker"
+        })
+    );
+    assert!(BbseStreamReader::new(&[][..]).read_value(0u8, 4).is_err());
+}
+
+#[test]
+fn test_unbounded() {
+    let mut writer = BbseStreamWriter::new(Vec::new());
+    for run in [0u64, 7, 1, 300, 65_000] {
+        writer.write_unbounded(run).unwrap();
+        writer.write_value(0u8, 4, 3).unwrap();
+    }
+    let bytes = writer.finish().unwrap();
+
+    let mut reader = BbseStreamReader::new(Trickle(&bytes));
+    for run in [0u64, 7, 1, 300, 65_000] {
+        assert_eq!(reader.read_unbounded::<u64>().unwrap(), run);
+        assert_eq!(reader.read_value(0u8, 4).unwrap(), 3);
+    }
+    assert!(reader.is_at_end().unwrap());
+}
+
+#[test]
+fn test_large_stream() {
+    let mut writer = BbseStreamWriter::new(Vec::new());
+    for i in 0..100_000u32 {
+        writer.write_value(0, 1 << 20, i * 10).unwrap();
+    }
+    assert_eq!(writer.bit_len(), 2_000_000);
+    let bytes = writer.finish().unwrap();
+    assert_eq!(bytes.len(), 250_001);
+
+    let mut reader = BbseStreamReader::new(&bytes[..]);
+    for i in 0..100_000u32 {
+        assert_eq!(reader.read_value(0, 1 << 20).unwrap(), i * 10);
+    }
+    assert_eq!(reader.position(), 2_000_000);
+    assert!(reader.is_at_end().unwrap());
+}
+
+#[test]
+fn test_errors() {
+    let mut writer = BbseStreamWriter::new(Vec::new());
+    let e = writer.write_value(0u8, 10, 10).unwrap_err();
+    assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+    assert!(matches!(
+        bbse_error(&e),
+        Some(BbseError::TargetOutOfBounds { .. })
+    ));
+    assert_eq!(writer.bit_len(), 0);
+
+    let bytes = [0b1011_0000];
+    let mut reader = BbseStreamReader::new(&bytes[..]);
+    assert_eq!(reader.read_value(0u8, 4).unwrap(), 2);
+    let e = reader.read_value(0u8, 4).unwrap_err();
+    assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof);
+    assert_eq!(
+        bbse_error(&e),
+        Some(&BbseError::UnexpectedEnd { position: 2 })
+    );
+
+    let mut reader = BbseStreamReader::new(&[0xff, 0x00][..]);
+    let e = reader.read_value(0u16, 1 << 12).unwrap_err();
+    assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+    assert!(matches!(
+        bbse_error(&e),
+        Some(BbseError::InvalidFormat { .. })
+    ));
+}
+
+proptest! {
+    #[test]
+    fn roundtrip(values in prop::collection::vec((1u32..5000, any::<u32>()), 0..200)) {
+        let mut writer = BbseStreamWriter::new(Vec::new());
+        for &(n, v) in &values {
+            writer.write_value(0, n, v % n).unwrap();
+        }
+        let bits = writer.bit_len();
+        let bytes = writer.finish().unwrap();
+        prop_assert_eq!(bytes.len() as u64, bits / 8 + 1);
+
+        let mut reader = BbseStreamReader::new(Trickle(&bytes));
+        for &(n, v) in &values {
+            prop_assert_eq!(reader.read_value(0, n).unwrap(), v % n);
+        }
+        prop_assert!(reader.is_at_end().unwrap());
+    }
+}
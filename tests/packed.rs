@@ -0,0 +1,76 @@
+#![cfg(feature = "alloc")]
+
+use bbse::{BbseError, BbseWriter, PackedStack};
+
+#[test]
+fn test_push_pop_lifo() {
+    let mut stack = PackedStack::new(-300i32, 300);
+    let values: Vec<i32> = (-300..300).step_by(7).collect();
+    for &v in &values {
+        stack.push(v);
+    }
+    assert_eq!(stack.len(), values.len());
+    assert_eq!(stack.decode_all(), values);
+
+    for &v in values.iter().rev() {
+        assert_eq!(stack.peek(), Some(v));
+        assert_eq!(stack.pop(), Some(v));
+    }
+    assert_eq!(stack.pop(), None);
+    assert!(stack.is_empty());
+    assert_eq!(stack.bit_len(), 0);
+}
+
+#[test]
+fn test_bit_len_is_sum_of_stream_paths() {
+    let mut stack = PackedStack::new(0u16, 1000);
+    let mut writer = BbseWriter::new();
+    for v in (0..1000).step_by(13) {
+        stack.push(v);
+        writer.write(0u16, 1000, v).unwrap();
+    }
+    assert_eq!(stack.bit_len(), writer.bit_len());
+    assert_eq!(stack.byte_len(), stack.bit_len().div_ceil(8));
+}
+
+#[test]
+fn test_interleaved_push_pop() {
+    let mut stack = PackedStack::new(0u8, 3);
+    stack.push(2);
+    stack.push(0);
+    assert_eq!(stack.pop(), Some(0));
+    stack.push(1);
+    stack.push(1);
+    assert_eq!(stack.decode_all(), vec![2, 1, 1]);
+    stack.clear();
+    assert!(stack.is_empty());
+}
+
+#[test]
+fn test_single_value_range() {
+    let mut stack = PackedStack::new(7u64, 8);
+    for _ in 0..5 {
+        stack.push(7);
+    }
+    assert_eq!(stack.bit_len(), 0);
+    assert_eq!(stack.len(), 5);
+    assert_eq!(stack.decode_all(), vec![7; 5]);
+}
+
+#[test]
+fn test_errors() {
+    assert!(matches!(
+        PackedStack::try_new(5u8, 5),
+        Err(BbseError::EmptyRange { .. })
+    ));
+    let mut stack = PackedStack::new(0u8, 10);
+    assert_eq!(
+        stack.try_push(10),
+        Err(BbseError::TargetOutOfBounds {
+            target: 10u8.into(),
+            start: 0u8.into(),
+            end: 10u8.into(),
+        })
+    );
+    assert!(stack.is_empty());
+}
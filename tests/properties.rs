@@ -0,0 +1,81 @@
+#![cfg(feature = "alloc")]
+
+use bbse::{decode, decode_from, encode, encode_from, try_decode, try_decode_from};
+use proptest::prelude::*;
+
+/// Ranges whose upper end sits near `usize::MAX`, where `(lo + hi) / 2` overflows.
+fn high_range() -> impl Strategy<Value = (usize, usize, usize)> {
+    (1usize..=1 << 20, 0usize..=1 << 20).prop_flat_map(|(len, gap)| {
+        let end = usize::MAX - gap;
+        let start = end - len;
+        (Just(start), Just(end), start..end)
+    })
+}
+
+/// Ranges spanning (almost) the whole `usize` domain.
+fn wide_range() -> impl Strategy<Value = (usize, usize, usize)> {
+    (0usize..=1 << 20, 0usize..=1 << 20).prop_flat_map(|(low, gap)| {
+        let end = usize::MAX - gap;
+        (Just(low), Just(end), low..end)
+    })
+}
+
+proptest! {
+    #[test]
+    fn roundtrip_near_usize_max((start, end, target) in high_range()) {
+        let path = encode(start, end, target);
+        prop_assert_eq!(decode(start, end, &path), target);
+        prop_assert_eq!(try_decode(start, end, &path), Ok(target));
+    }
+
+    #[test]
+    fn roundtrip_full_domain((start, end, target) in wide_range()) {
+        let path = encode(start, end, target);
+        prop_assert!(path.len() <= usize::BITS as usize);
+        prop_assert_eq!(decode(start, end, &path), target);
+        prop_assert_eq!(try_decode(start, end, &path), Ok(target));
+    }
+
+    #[test]
+    fn roundtrip_from_near_usize_max(
+        (start, end, target) in high_range(),
+        seed in any::<usize>(),
+    ) {
+        prop_assume!(end - start >= 2);
+        let midpoint = start + 1 + seed % (end - start - 1);
+        let path = encode_from(start, end, target, midpoint);
+        prop_assert_eq!(decode_from(start, end, &path, midpoint), target);
+        prop_assert_eq!(try_decode_from(start, end, &path, midpoint), Ok(target));
+    }
+
+    #[test]
+    fn roundtrip_from_full_domain(
+        (start, end, target) in wide_range(),
+        seed in any::<usize>(),
+    ) {
+        prop_assume!(end - start >= 2);
+        let midpoint = start + 1 + seed % (end - start - 1);
+        let path = encode_from(start, end, target, midpoint);
+        prop_assert_eq!(decode_from(start, end, &path, midpoint), target);
+        prop_assert_eq!(try_decode_from(start, end, &path, midpoint), Ok(target));
+    }
+}
+
+#[test]
+fn test_extreme_values() {
+    for (start, end) in [
+        (0, usize::MAX),
+        (usize::MAX - 1, usize::MAX),
+        (usize::MAX / 2, usize::MAX),
+    ] {
+        for target in [start, end - 1, start + (end - start) / 2] {
+            let path = encode(start, end, target);
+            assert_eq!(decode(start, end, &path), target);
+
+            if end - start >= 2 {
+                let path = encode_from(start, end, target, end - 1);
+                assert_eq!(decode_from(start, end, &path, end - 1), target);
+            }
+        }
+    }
+}
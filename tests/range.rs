@@ -0,0 +1,79 @@
+#![cfg(feature = "alloc")]
+
+use bbse::{
+    decode, decode_range, encode, encode_range, try_decode_range, try_encode_range, BbseError,
+};
+use core::ops::{Bound, RangeInclusive};
+
+#[test]
+fn test_full_u8_domain() {
+    for v in u8::MIN..=u8::MAX {
+        let path = encode_range(u8::MIN..=u8::MAX, v);
+        assert_eq!(path, encode(0u16, 256, v as u16), "value {}", v);
+        assert_eq!(decode_range::<u8>(.., &path), v);
+        assert_eq!(try_decode_range(0..=255u8, &path), Ok(v));
+    }
+}
+
+#[test]
+fn test_full_wide_domains() {
+    for v in [u64::MIN, 1, u64::MAX / 2, u64::MAX - 1, u64::MAX] {
+        let path = encode_range(u64::MIN..=u64::MAX, v);
+        assert!(path.len() <= 64);
+        assert_eq!(try_decode_range(u64::MIN..=u64::MAX, &path), Ok(v));
+    }
+    for v in [i128::MIN, -1, 0, i128::MAX] {
+        let path = encode_range(.., v);
+        assert_eq!(try_decode_range::<i128>(.., &path), Ok(v));
+    }
+    for v in [0, u128::MAX] {
+        let path = encode_range(.., v);
+        assert!(path.len() <= 128);
+        assert_eq!(try_decode_range::<u128>(.., &path), Ok(v));
+    }
+}
+
+#[test]
+fn test_range_forms_match_half_open() {
+    for v in 10..20 {
+        let expected = encode(10, 20, v);
+        assert_eq!(encode_range(10..20, v), expected);
+        assert_eq!(encode_range(10..=19, v), expected);
+        assert_eq!(
+            encode_range((Bound::Excluded(9), Bound::Included(19)), v),
+            expected
+        );
+        assert_eq!(decode(10, 20, &expected), v);
+        assert_eq!(decode_range(10..=19, &expected), v);
+    }
+    for v in 0u8..10 {
+        assert_eq!(encode_range(..10u8, v), encode(0u8, 10, v));
+    }
+}
+
+#[test]
+fn test_range_errors() {
+    assert_eq!(
+        try_encode_range(RangeInclusive::new(5, 4), 5),
+        Err(BbseError::EmptyRange {
+            start: 5.into(),
+            end: 4.into()
+        })
+    );
+    assert_eq!(
+        try_encode_range((Bound::Excluded(u128::MAX), Bound::Unbounded), 0),
+        Err(BbseError::EmptyRange {
+            start: u128::MAX.into(),
+            end: u128::MAX.into()
+        })
+    );
+    assert_eq!(
+        try_encode_range(0..0u8, 0).unwrap_err().to_string(),
+        "Invalid range: start (0) >= end (0)"
+    );
+    assert_eq!(
+        try_encode_range(1..=8u8, 9).unwrap_err().to_string(),
+        "target (9) out of bounds [1, 8]"
+    );
+    assert!(try_decode_range(7..=7u8, &encode(0, 8, 0)).is_err());
+}
@@ -0,0 +1,155 @@
+#![cfg(feature = "alloc")]
+
+use bbse::{BbseError, BbseReader, BbseWriter, RecordSchema};
+use proptest::prelude::*;
+
+fn schema() -> RecordSchema {
+    RecordSchema::new()
+        .field("channel", 0u8, 3)
+        .field("delta", -64i8, 64)
+        .field("offset", 0u16, 1000)
+}
+
+#[test]
+fn test_same_bits_as_writer() {
+    let schema = schema();
+    let mut record = schema.record();
+    assert_eq!(record.get::<u8>("channel"), 0);
+    assert_eq!(record.get::<i8>("delta"), -64);
+    record.set("channel", 1u8);
+    record.set("delta", 63i8);
+    record.set("offset", 999u16);
+
+    let mut writer = BbseWriter::new();
+    writer.write(0u8, 3, 1).unwrap();
+    writer.write(-64i8, 64, 63).unwrap();
+    writer.write(0u16, 1000, 999).unwrap();
+    assert_eq!(schema.encode(&record), writer.into_path());
+}
+
+#[test]
+fn test_stream_of_records() {
+    let schema = schema();
+    let mut records = vec![];
+    for i in 0..50u16 {
+        let mut record = schema.record();
+        record.set("channel", (i % 3) as u8);
+        record.set("delta", (i as i8) - 25);
+        record.set("offset", i * 20);
+        records.push(record);
+    }
+    let path = schema.encode_all(&records);
+    assert_eq!(schema.decode_all(&path, 50), Ok(records.clone()));
+    assert!(matches!(
+        schema.decode_all(&path, 49),
+        Err(BbseError::PathTooLong { .. })
+    ));
+    assert!(matches!(
+        schema.decode_all(&path, 51),
+        Err(BbseError::UnexpectedEnd { .. })
+    ));
+
+    // Records interleave with other values in a plain stream.
+    let mut writer = BbseWriter::new();
+    writer.write_unbounded(2u32).unwrap();
+    schema.write(&mut writer, &records[7]);
+    schema.write(&mut writer, &records[8]);
+    let path = writer.into_path();
+    let mut reader = BbseReader::new(&path);
+    assert_eq!(reader.read_unbounded::<u32>(), Ok(2));
+    assert_eq!(schema.read(&mut reader).as_ref(), Ok(&records[7]));
+    assert_eq!(schema.read(&mut reader).as_ref(), Ok(&records[8]));
+    assert!(reader.is_empty());
+}
+
+#[test]
+fn test_field_errors() {
+    let schema = schema();
+    assert_eq!(schema.len(), 3);
+    assert_eq!(schema.index_of("offset"), Some(2));
+    assert_eq!(
+        schema.names().collect::<Vec<_>>(),
+        ["channel", "delta", "offset"]
+    );
+
+    let mut record = schema.record();
+    assert_eq!(
+        record.try_get::<u8>("missing"),
+        Err(BbseError::UnknownField)
+    );
+    assert_eq!(
+        record.try_get::<u16>("delta"),
+        Err(BbseError::FieldType { index: 1 })
+    );
+    assert_eq!(
+        record.try_set("offset", 1000u16),
+        Err(BbseError::TargetOutOfBounds {
+            target: 1000u16.into(),
+            start: 0u16.into(),
+            end: 1000u16.into(),
+        })
+    );
+    assert_eq!(record.get::<u16>("offset"), 0);
+
+    let wide = RecordSchema::new().field("id", 0u128, u128::MAX);
+    let mut record = wide.record();
+    record.set("id", u128::MAX - 1);
+    assert_eq!(
+        record.try_get::<i128>("id"),
+        Err(BbseError::FieldType { index: 0 })
+    );
+    assert_eq!(record.get::<u128>("id"), u128::MAX - 1);
+
+    assert_eq!(
+        RecordSchema::new().try_field("x", 5i32, 5),
+        Err(BbseError::EmptyRange {
+            start: 5i32.into(),
+            end: 5i32.into(),
+        })
+    );
+    assert_eq!(
+        schema.clone().try_field("delta", 0u8, 2),
+        Err(BbseError::DuplicateField { name: "delta" })
+    );
+}
+
+#[test]
+#[should_panic(expected = "field `delta` is already declared")]
+fn test_duplicate_field() {
+    let _ = schema().field("delta", 0u8, 2);
+}
+
+#[test]
+#[should_panic(expected = "record belongs to a different schema")]
+fn test_foreign_record() {
+    let other = RecordSchema::new().field("channel", 0u8, 3);
+    let record = other.record();
+    let _ = schema().encode(&record);
+}
+
+#[test]
+fn test_empty_schema() {
+    let schema = RecordSchema::new();
+    assert!(schema.is_empty());
+    assert!(schema.encode(&schema.record()).is_empty());
+    assert_eq!(schema.decode_all(&Default::default(), 3).unwrap().len(), 3);
+}
+
+proptest! {
+    #[test]
+    fn roundtrip(fields in prop::collection::vec((any::<i64>(), 1u64..1 << 40, any::<u64>()), 1..12)) {
+        const NAMES: [&str; 12] = ["a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l"];
+        let mut schema = RecordSchema::new();
+        for (i, &(start, width, _)) in fields.iter().enumerate() {
+            let start = start.min(i64::MAX - width as i64);
+            schema = schema.field(NAMES[i], start, start + width as i64);
+        }
+        let mut record = schema.record();
+        for (i, &(start, width, v)) in fields.iter().enumerate() {
+            let start = start.min(i64::MAX - width as i64);
+            record.set(NAMES[i], start + (v % width) as i64);
+        }
+        let path = schema.encode(&record);
+        prop_assert_eq!(schema.decode(&path), Ok(record));
+    }
+}
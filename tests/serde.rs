@@ -0,0 +1,55 @@
+#![cfg(feature = "serde")]
+
+use bbse::{encode, BBSEStack, BbsePath};
+
+fn sample_stack() -> BBSEStack {
+    let mut stack = BBSEStack::new();
+    for v in [0, 1, 128, 255, 77] {
+        stack.push(encode(0, 256, v));
+    }
+    stack
+}
+
+#[test]
+fn test_path_json_is_bit_string() {
+    let path = "0110".parse::<BbsePath>().unwrap();
+    let json = serde_json::to_string(&path).unwrap();
+    assert_eq!(json, "\"0110\"");
+    assert_eq!(serde_json::from_str::<BbsePath>(&json).unwrap(), path);
+
+    assert_eq!(
+        serde_json::from_str::<BbsePath>("\"\"").unwrap(),
+        BbsePath::new()
+    );
+    assert!(serde_json::from_str::<BbsePath>("\"01x\"").is_err());
+}
+
+#[test]
+fn test_path_bincode_is_packed() {
+    let path = "101100001".parse::<BbsePath>().unwrap();
+    let bytes = bincode::serialize(&path).unwrap();
+    // u64 bit length, u64 byte count, then the packed bytes
+    assert_eq!(bytes.len(), 8 + 8 + 2);
+    assert_eq!(&bytes[16..], &[0b1011_0000, 0b1000_0000]);
+    assert_eq!(bincode::deserialize::<BbsePath>(&bytes).unwrap(), path);
+
+    let mut bad = bytes.clone();
+    bad[0] = 17;
+    assert!(bincode::deserialize::<BbsePath>(&bad).is_err());
+}
+
+#[test]
+fn test_stack_roundtrip() {
+    let stack = sample_stack();
+
+    let json = serde_json::to_string(&stack).unwrap();
+    let expected: Vec<String> = stack.entries.iter().map(|p| p.to_string()).collect();
+    assert_eq!(json, serde_json::to_string(&expected).unwrap());
+    assert!(json.contains(",\"\","), "midpoint path is empty");
+    let restored: BBSEStack = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, stack);
+
+    let bytes = bincode::serialize(&stack).unwrap();
+    let restored: BBSEStack = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(restored.decode_all(0, 256), vec![0, 1, 128, 255, 77]);
+}
@@ -0,0 +1,122 @@
+#![cfg(feature = "alloc")]
+
+use std::collections::BTreeSet;
+
+use bbse::{BbseError, BbsePath, BbseSet, SortedSeq};
+use proptest::prelude::*;
+
+#[test]
+fn test_full_and_empty() {
+    let all: Vec<u8> = (0..=255).collect();
+    let full = BbseSet::new(u8::MAX, &all[..255]);
+    assert_eq!(full.bit_len(), 0);
+    assert_eq!(full.len(), 255);
+    assert!(full.contains(0) && full.contains(254) && !full.contains(255));
+
+    let empty = BbseSet::new(100u32, &[]);
+    assert!(empty.is_empty());
+    assert!(!empty.contains(0));
+    assert_eq!(empty.iter().next(), None);
+}
+
+#[test]
+fn test_from_set() {
+    let ids: BTreeSet<u64> = [7, 1 << 20, 3, 99, 1 << 30].into_iter().collect();
+    let set = BbseSet::from_set(1 << 32, &ids);
+    assert_eq!(set.universe(), 1 << 32);
+    assert_eq!(set.to_set(), ids);
+    for v in [0, 4, 98, 100, u32::MAX as u64] {
+        assert!(!set.contains(v));
+    }
+    assert_eq!(
+        BbseSet::try_from_set(1 << 10, &ids),
+        Err(BbseError::TargetOutOfBounds {
+            target: (1u64 << 20).into(),
+            start: 0u64.into(),
+            end: 1024u64.into(),
+        })
+    );
+}
+
+#[test]
+fn test_slices() {
+    let set = BbseSet::new(50i32, &[1, 1, 2, 30, 30, 49]);
+    assert_eq!(set.len(), 4);
+    assert_eq!(set.iter().collect::<Vec<_>>(), [1, 2, 30, 49]);
+    assert!(!set.contains(-1));
+    assert!(!set.contains(50));
+
+    assert_eq!(
+        BbseSet::try_new(50i32, &[1, 5, 4]),
+        Err(BbseError::NotSorted { position: 2 })
+    );
+    assert!(BbseSet::try_new(50i32, &[-1, 3]).is_err());
+    assert!(BbseSet::try_new(0u8, &[]).is_err());
+}
+
+#[test]
+fn test_from_parts() {
+    let set = BbseSet::new(1000u16, &[10, 20, 30]);
+    let rebuilt = BbseSet::from_parts(1000u16, 3, set.as_path().clone());
+    assert_eq!(rebuilt.as_ref(), Ok(&set));
+    assert_eq!(
+        BbseSet::from_parts(1000u16, 4, set.into_path()),
+        Err(BbseError::UnexpectedEnd { position: 0 })
+    );
+}
+
+#[test]
+fn test_from_parts_untrusted() {
+    let too_many = Some(BbseError::InvalidFormat {
+        reason: "more distinct values than the range holds",
+    });
+    assert_eq!(
+        BbseSet::from_parts(1000u16, 1001, BbsePath::new()).err(),
+        too_many
+    );
+    assert_eq!(
+        BbseSet::from_parts(u32::MAX, usize::MAX, BbsePath::new()).err(),
+        too_many
+    );
+    assert!(BbseSet::from_parts(0u8, 0, BbsePath::new()).is_err());
+
+    // A full universe costs no bits and is rebuilt without decoding it.
+    let full = BbseSet::from_parts(u32::MAX, u32::MAX as usize, BbsePath::new()).unwrap();
+    assert_eq!(full.len(), u32::MAX as usize);
+    assert!(full.contains(123_456_789));
+
+    // Corrupted bits: truncated, extended or cut short of a member.
+    let set = BbseSet::new(1 << 20, &[1u32, 5000, 70_000, 999_999]);
+    let bits = set.as_path();
+    let truncated: BbsePath = bits.iter().take(bits.len() - 1).collect();
+    assert!(BbseSet::from_parts(1 << 20, 4, truncated).is_err());
+    let mut extended = bits.clone();
+    extended.push(false);
+    assert!(matches!(
+        BbseSet::from_parts(1 << 20, 4, extended),
+        Err(BbseError::PathTooLong { .. })
+    ));
+    assert!(BbseSet::from_parts(1 << 20, 3, bits.clone()).is_err());
+}
+
+#[test]
+fn test_seq_contains() {
+    let values = [-3i16, -3, 0, 8, 8, 8, 200];
+    let seq = SortedSeq::new(-10i16, 300, &values);
+    for v in -10..300 {
+        assert_eq!(seq.contains(v), values.contains(&v), "{}", v);
+    }
+    assert!(!seq.contains(i16::MIN));
+}
+
+proptest! {
+    #[test]
+    fn roundtrip(ids in prop::collection::btree_set(0u32..5000, 0..300)) {
+        let set = BbseSet::from_set(5000, &ids);
+        prop_assert_eq!(set.len(), ids.len());
+        prop_assert_eq!(set.to_set(), ids.clone());
+        for v in (0..5000).step_by(61) {
+            prop_assert_eq!(set.contains(v), ids.contains(&v));
+        }
+    }
+}
@@ -0,0 +1,116 @@
+#![cfg(feature = "alloc")]
+
+use bbse::{encode, BbseError, BbsePath, SortedSeq};
+use proptest::prelude::*;
+
+#[test]
+fn test_dense_run_is_free() {
+    let all: Vec<u16> = (100..200).collect();
+    let seq = SortedSeq::new(100u16, 200, &all);
+    assert!(seq.is_strict());
+    assert_eq!(seq.bit_len(), 0);
+    assert_eq!(seq.decode(), all);
+}
+
+#[test]
+fn test_beats_independent_paths() {
+    let postings: Vec<u32> = (0..500).map(|i| i * 7 + i % 3).collect();
+    let seq = SortedSeq::new(0u32, 1 << 20, &postings);
+    let independent: usize = postings
+        .iter()
+        .map(|&v| encode(0u32, 1 << 20, v).len())
+        .sum();
+    assert!(
+        seq.bit_len() * 3 < independent,
+        "{} vs {}",
+        seq.bit_len(),
+        independent
+    );
+    assert_eq!(seq.decode(), postings);
+}
+
+#[test]
+fn test_non_strict() {
+    let values = [-5i8, -5, -5, 0, 0, 7, 7, 7, 7];
+    let seq = SortedSeq::new(-10i8, 10, &values);
+    assert!(!seq.is_strict());
+    assert_eq!(seq.decode(), values);
+
+    let same = SortedSeq::new(0u8, 255, &[9; 50]);
+    assert_eq!(same.decode(), vec![9; 50]);
+}
+
+#[test]
+fn test_edges() {
+    let empty = SortedSeq::<u8>::new(0, 10, &[]);
+    assert!(empty.is_empty());
+    assert_eq!(empty.decode(), vec![]);
+
+    let top = SortedSeq::new(u128::MAX - 3, u128::MAX, &[u128::MAX - 3, u128::MAX - 1]);
+    assert_eq!(top.decode(), vec![u128::MAX - 3, u128::MAX - 1]);
+}
+
+#[test]
+fn test_errors() {
+    assert_eq!(
+        SortedSeq::try_new(0u8, 10, &[1, 3, 2]),
+        Err(BbseError::NotSorted { position: 2 })
+    );
+    assert!(SortedSeq::try_new(0u8, 10, &[1, 10]).is_err());
+    assert!(SortedSeq::try_new(5u8, 5, &[]).is_err());
+}
+
+#[test]
+fn test_from_parts() {
+    let seq = SortedSeq::new(0u32, 1000, &[10, 20, 30, 999]);
+    let rebuilt = SortedSeq::from_parts(0u32, 1000, 4, true, seq.as_path().clone());
+    assert_eq!(rebuilt.as_ref(), Ok(&seq));
+
+    let mut long = seq.as_path().clone();
+    long.push(true);
+    assert!(matches!(
+        SortedSeq::from_parts(0u32, 1000, 4, true, long),
+        Err(BbseError::PathTooLong { .. })
+    ));
+    assert_eq!(
+        SortedSeq::from_parts(0u32, 1000, 5, true, seq.into_path()),
+        Err(BbseError::UnexpectedEnd { position: 0 })
+    );
+    assert!(SortedSeq::from_parts(0u32, 3, 4, true, BbsePath::new()).is_err());
+}
+
+#[test]
+fn test_from_parts_huge_len() {
+    // Oversized lengths are rejected from the bits, without allocating.
+    assert_eq!(
+        SortedSeq::from_parts(0u32, 1000, usize::MAX, false, BbsePath::new()),
+        Err(BbseError::UnexpectedEnd { position: 0 })
+    );
+    assert!(SortedSeq::from_parts(0u64, 1 << 40, usize::MAX, true, BbsePath::new()).is_err());
+    // Values pinned down by their bounds cost no bits, in any number.
+    let seq = SortedSeq::from_parts(7u8, 8, usize::MAX, false, BbsePath::new()).unwrap();
+    assert!(seq.contains(7));
+    let seq = SortedSeq::from_parts(0u32, u32::MAX, u32::MAX as usize, true, BbsePath::new());
+    assert!(seq.unwrap().contains(12345));
+
+    // Repeated values can take fewer bits than there are values.
+    let same = vec![255u16; 1000];
+    let seq = SortedSeq::new(0, 256, &same);
+    assert!(seq.bit_len() < same.len());
+    let rebuilt = SortedSeq::from_parts(0, 256, 1000, false, seq.as_path().clone());
+    assert_eq!(rebuilt.map(|s| s.decode()), Ok(same));
+}
+
+proptest! {
+    #[test]
+    fn roundtrip(mut values in prop::collection::vec(-1000i32..1000, 0..200), dedup in any::<bool>()) {
+        values.sort();
+        if dedup {
+            values.dedup();
+        }
+        let seq = SortedSeq::new(-1000i32, 1000, &values);
+        prop_assert_eq!(seq.decode(), values);
+        let parts = (seq.len(), seq.is_strict(), seq.as_path().clone());
+        prop_assert_eq!(SortedSeq::from_parts(-1000, 1000, parts.0, parts.1, parts.2), Ok(seq));
+    }
+}
@@ -0,0 +1,119 @@
+#![cfg(feature = "alloc")]
+
+use bbse::{
+    decode_with, encode, encode_from, encode_with, try_decode_with, try_encode_with, BbseError,
+    BbsePath, Center, MidpointStrategy, Ratio,
+};
+use proptest::prelude::*;
+
+#[test]
+fn test_center_matches_encode() {
+    for end in 1..40 {
+        for v in 0..end {
+            assert_eq!(encode_with(0, end, v, &Center), encode(0, end, v));
+            assert_eq!(encode_with(0, end, v, &Ratio::HALF), encode(0, end, v));
+        }
+    }
+    for v in -50i8..50 {
+        assert_eq!(encode_with(-50i8, 50, v, &Center), encode(-50i8, 50, v));
+    }
+}
+
+#[test]
+fn test_root_closure_matches_encode_from() {
+    let root = |lo: u32, hi: u32, depth: usize| {
+        if depth == 0 {
+            3
+        } else {
+            Center.split(lo, hi, depth)
+        }
+    };
+    for v in 0..20 {
+        assert_eq!(encode_with(0, 20, v, &root), encode_from(0, 20, v, 3));
+        assert_eq!(decode_with(0, 20, &encode_from(0, 20, v, 3), &root), v);
+    }
+}
+
+#[test]
+fn test_biased_ratios() {
+    let left = Ratio::GOLDEN;
+    let right = Ratio::GOLDEN.mirror();
+    for v in 0..=255u8 {
+        for strategy in [left, right, Ratio::new(0, 1), Ratio::new(1, 1)] {
+            let path = encode_with(u8::MIN, u8::MAX, v.min(254), &strategy);
+            assert_eq!(
+                try_decode_with(u8::MIN, u8::MAX, &path, &strategy),
+                Ok(v.min(254))
+            );
+        }
+    }
+    assert!(encode_with(0, 256, 10, &left).len() < encode_with(0, 256, 10, &right).len());
+    assert!(encode_with(0, 256, 245, &left).len() > encode_with(0, 256, 245, &right).len());
+    assert_eq!(encode_with(0, 256, 200, &Ratio::new(0, 1)).len(), 199);
+}
+
+#[test]
+fn test_table_driven() {
+    // A fixed split per level, falling back to the center once out of range.
+    let table = [10u16, 5, 2];
+    let strategy = |lo: u16, hi: u16, depth: usize| match table.get(depth) {
+        Some(&mid) if lo < mid && mid <= hi => mid,
+        _ => Center.split(lo, hi, depth),
+    };
+    assert!(encode_with(0, 100, 10, &strategy).is_empty());
+    assert_eq!(encode_with(0, 100, 5, &strategy), "0");
+    assert_eq!(encode_with(0, 100, 2, &strategy), "00");
+    for v in 0..100 {
+        let path = encode_with(0, 100, v, &strategy);
+        assert_eq!(try_decode_with(0, 100, &path, &strategy), Ok(v));
+    }
+}
+
+#[test]
+fn test_invalid_split() {
+    let stuck = |lo: i32, _hi: i32, _depth: usize| lo;
+    assert_eq!(
+        try_encode_with(-4, 4, 1, &stuck),
+        Err(BbseError::InvalidSplit {
+            midpoint: (-4).into(),
+            lo: (-4).into(),
+            hi: 3.into(),
+            depth: 0,
+        })
+    );
+    let late = |lo: i32, hi: i32, depth: usize| if depth < 2 { lo + 1 } else { hi + 1 };
+    assert!(matches!(
+        try_decode_with(0, 8, &"11".parse().unwrap(), &late),
+        Err(BbseError::InvalidSplit { depth: 2, .. })
+    ));
+}
+
+#[test]
+#[should_panic(expected = "split (0) at depth 0 must satisfy 0 < split <= 7")]
+fn test_invalid_split_panics() {
+    let _ = encode_with(0, 8, 3, &|lo: u8, _hi: u8, _depth: usize| lo);
+}
+
+#[test]
+fn test_checked_decode_rejects_unreachable() {
+    let strategy = Ratio::new(1, 3);
+    for len in 0..=6 {
+        for bits in 0..(1u32 << len) {
+            let path: BbsePath = (0..len).rev().map(|i| bits >> i & 1 == 1).collect();
+            if let Ok(v) = try_decode_with(0, 30, &path, &strategy) {
+                assert_eq!(encode_with(0, 30, v, &strategy), path);
+            }
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn roundtrip_ratio(num in 1u64..100, v in any::<u64>(), a in any::<u64>(), b in any::<u64>()) {
+        let (start, end) = (a.min(b), a.max(b));
+        prop_assume!(start <= v && v < end);
+        let strategy = Ratio::new(num, 100);
+        let path = encode_with(start, end, v, &strategy);
+        prop_assert_eq!(try_decode_with(start, end, &path, &strategy), Ok(v));
+    }
+}
@@ -0,0 +1,128 @@
+#![cfg(feature = "alloc")]
+
+use bbse::{BbseError, BbsePath, BbseReader, BbseWriter};
+use proptest::prelude::*;
+
+fn code(start: u32, end: u32, value: u32) -> BbsePath {
+    let mut writer = BbseWriter::new();
+    writer.write(start, end, value).unwrap();
+    writer.into_path()
+}
+
+#[test]
+fn test_codes_are_prefix_free_and_minimal() {
+    for n in 1..70u32 {
+        let codes: Vec<_> = (0..n).map(|v| code(0, n, v)).collect();
+        let min = n.ilog2() as usize;
+        let max = n.next_power_of_two().ilog2() as usize;
+        for (a, ca) in codes.iter().enumerate() {
+            assert!(min <= ca.len() && ca.len() <= max, "n={} value={}", n, a);
+            for (b, cb) in codes.iter().enumerate() {
+                if a != b {
+                    assert!(!cb.starts_with(ca), "n={}: {} prefixes {}", n, ca, cb);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_mixed_stream_roundtrip() {
+    let mut writer = BbseWriter::new();
+    for i in 0..100u32 {
+        writer.write(0u8, 3, (i % 3) as u8).unwrap();
+        writer.write(-64i16, 64, (i as i16 % 128) - 64).unwrap();
+        writer.write(0u32, 1000, i * 7).unwrap();
+        writer.write_range(.., u64::MAX - i as u64).unwrap();
+    }
+    let bits = writer.into_path();
+
+    let mut reader = BbseReader::new(&bits);
+    for i in 0..100u32 {
+        assert_eq!(reader.read(0u8, 3), Ok((i % 3) as u8));
+        assert_eq!(reader.read(-64i16, 64), Ok((i as i16 % 128) - 64));
+        assert_eq!(reader.read(0u32, 1000), Ok(i * 7));
+        assert_eq!(reader.read_range::<u64>(..), Ok(u64::MAX - i as u64));
+    }
+    assert!(reader.is_empty());
+}
+
+#[test]
+fn test_single_value_ranges_cost_nothing() {
+    let mut writer = BbseWriter::new();
+    writer.write(42, 43, 42).unwrap();
+    writer.write_range(7..=7u8, 7).unwrap();
+    assert_eq!(writer.bit_len(), 0);
+}
+
+#[test]
+fn test_stream_errors() {
+    let mut writer = BbseWriter::new();
+    assert!(matches!(
+        writer.write(0, 8, 8),
+        Err(BbseError::TargetOutOfBounds { .. })
+    ));
+    writer.write(0, 8, 3).unwrap();
+    writer.write(0, 256, 3).unwrap();
+    let bits = writer.into_path();
+
+    let truncated: BbsePath = bits.iter().take(bits.len() - 1).collect();
+    let mut reader = BbseReader::new(&truncated);
+    assert_eq!(reader.read(0, 8), Ok(3));
+    assert_eq!(
+        reader.read(0, 256),
+        Err(BbseError::UnexpectedEnd { position: 3 })
+    );
+    assert_eq!(reader.position(), 3);
+    assert_eq!(reader.remaining(), 7);
+}
+
+#[test]
+fn test_root_midpoint() {
+    let mut writer = BbseWriter::new();
+    for v in 0..100u16 {
+        writer.write_from(0, 100, v, 3).unwrap();
+    }
+    assert_eq!(
+        writer.write_from(0u16, 100, 5, 0),
+        Err(BbseError::InvalidMidpoint {
+            midpoint: 0u16.into(),
+            start: 0u16.into(),
+            end: 100u16.into(),
+        })
+    );
+    let bits = writer.into_path();
+
+    let mut reader = BbseReader::new(&bits);
+    for v in 0..100u16 {
+        assert_eq!(reader.read_from(0, 100, 3), Ok(v));
+    }
+    assert!(reader.is_empty());
+    assert!(reader.read_from(0u16, 100, 100).is_err());
+}
+
+proptest! {
+    #[test]
+    fn roundtrip_random_ranges(values in prop::collection::vec((any::<i64>(), any::<i64>(), any::<u64>()), 0..50)) {
+        let items: Vec<_> = values
+            .into_iter()
+            .filter(|(a, b, _)| a != b)
+            .map(|(a, b, seed)| {
+                let (start, end) = (a.min(b), a.max(b));
+                let span = (end as i128 - start as i128) as u128;
+                (start, end, (start as i128 + (seed as u128 % span) as i128) as i64)
+            })
+            .collect();
+
+        let mut writer = BbseWriter::new();
+        for &(start, end, v) in &items {
+            writer.write(start, end, v).unwrap();
+        }
+        let bits = writer.into_path();
+        let mut reader = BbseReader::new(&bits);
+        for &(start, end, v) in &items {
+            prop_assert_eq!(reader.read(start, end), Ok(v));
+        }
+        prop_assert!(reader.is_empty());
+    }
+}
@@ -0,0 +1,133 @@
+#![cfg(feature = "alloc")]
+
+use std::collections::HashMap;
+
+use bbse::{decode_with, encode, encode_with, BbseError, BbsePath, SplitTree};
+use proptest::prelude::*;
+
+/// Total bits of every value weighted by its count.
+fn total_bits(tree: &SplitTree<u32>, counts: &[u64]) -> u64 {
+    (0..counts.len() as u32)
+        .map(|v| counts[v as usize] * tree.encode(v).len() as u64)
+        .sum()
+}
+
+/// Cheapest total over every possible choice of midpoints, by exhaustive search.
+fn brute_force(counts: &[u64]) -> u64 {
+    fn best(counts: &[u64], lo: usize, hi: usize, memo: &mut HashMap<(usize, usize), u64>) -> u64 {
+        if lo == hi {
+            return 0;
+        }
+        if let Some(&cost) = memo.get(&(lo, hi)) {
+            return cost;
+        }
+        let first = if lo == 0 { 0 } else { lo + 1 };
+        let weight: u64 = counts[first..=hi].iter().sum();
+        let cost = (lo + 1..=hi)
+            .map(|mid| {
+                weight - counts[mid] + best(counts, lo, mid - 1, memo) + best(counts, mid, hi, memo)
+            })
+            .min()
+            .unwrap();
+        memo.insert((lo, hi), cost);
+        cost
+    }
+    best(counts, 0, counts.len() - 1, &mut HashMap::new())
+}
+
+#[test]
+fn test_uniform_is_no_worse_than_center() {
+    let counts = [1; 64];
+    let tree = SplitTree::new(0u32, 64, &counts);
+    let center: u64 = (0..64).map(|v| encode(0u32, 64, v).len() as u64).sum();
+    assert!(total_bits(&tree, &counts) <= center);
+}
+
+#[test]
+fn test_skewed() {
+    let mut counts = [1; 100];
+    counts[0] = 1000;
+    counts[1] = 500;
+    let tree = SplitTree::new(0u32, 100, &counts);
+    assert_eq!(tree.encode(1), BbsePath::new());
+    assert_eq!(tree.encode(0), "0");
+    assert_eq!(total_bits(&tree, &counts), brute_force(&counts));
+}
+
+#[test]
+fn test_single_value() {
+    let tree = SplitTree::new(7i8, 8, &[5]);
+    assert!(tree.encode(7).is_empty());
+    assert_eq!(tree.decode(&BbsePath::new()), 7);
+    assert_eq!(SplitTree::<i8>::from_bytes(&tree.to_bytes()), Ok(tree));
+}
+
+#[test]
+fn test_as_strategy() {
+    let counts: Vec<u64> = (0..40).map(|v| 40 - v).collect();
+    let tree = SplitTree::new(10u32, 50, &counts);
+    for v in 10..50 {
+        let path = encode_with(10u32, 50, v, &tree);
+        assert_eq!(path, tree.encode(v));
+        assert_eq!(decode_with(10u32, 50, &path, &tree), v);
+        assert_eq!(tree.try_decode(&path), Ok(v));
+    }
+    // Other ranges fall back to the center split.
+    assert_eq!(encode_with(0u32, 8, 5, &tree), encode(0u32, 8, 5));
+}
+
+#[test]
+fn test_errors() {
+    assert_eq!(
+        SplitTree::try_new(0u8, 4, &[1, 2, 3]),
+        Err(BbseError::HistogramMismatch {
+            len: 3,
+            expected: 4
+        })
+    );
+    assert!(SplitTree::try_new(4u8, 4, &[]).is_err());
+
+    let tree = SplitTree::new(0u8, 4, &[1, 2, 3, 4]);
+    assert!(tree.try_encode(4).is_err());
+    let bytes = tree.to_bytes();
+    assert!(SplitTree::<i8>::from_bytes(&bytes).is_err());
+    let wide = SplitTree::new(0u128, 4, &[1, 2, 3, 4]).to_bytes();
+    assert_eq!(
+        SplitTree::<i128>::from_bytes(&wide),
+        Err(BbseError::InvalidFormat {
+            reason: "integer type does not match"
+        })
+    );
+    assert!(SplitTree::<u128>::from_bytes(&wide).is_ok());
+    assert!(SplitTree::<u8>::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert!(SplitTree::<u8>::from_bytes(&trailing).is_err());
+    let mut outside = bytes.clone();
+    outside[9] = 3;
+    assert_eq!(
+        SplitTree::<u8>::from_bytes(&outside),
+        Err(BbseError::InvalidFormat {
+            reason: "midpoint outside its search state"
+        })
+    );
+}
+
+proptest! {
+    #[test]
+    fn optimal(counts in prop::collection::vec(0u64..50, 1..24)) {
+        let tree = SplitTree::new(0u32, counts.len() as u32, &counts);
+        prop_assert_eq!(total_bits(&tree, &counts), brute_force(&counts));
+    }
+
+    #[test]
+    fn roundtrip(counts in prop::collection::vec(0u64..1000, 1..200), start in -1000i32..1000) {
+        let end = start + counts.len() as i32;
+        let tree = SplitTree::new(start, end, &counts);
+        let restored = SplitTree::<i32>::from_bytes(&tree.to_bytes()).unwrap();
+        prop_assert_eq!(&restored, &tree);
+        for v in start..end {
+            prop_assert_eq!(restored.try_decode(&tree.encode(v)), Ok(v));
+        }
+    }
+}
@@ -0,0 +1,109 @@
+#![cfg(feature = "alloc")]
+
+use bbse::{
+    decode_unbounded, encode_unbounded, try_decode_unbounded, try_encode_unbounded, BbseError,
+    BbsePath, BbseReader, BbseWriter,
+};
+use proptest::prelude::*;
+
+#[test]
+fn test_gamma_lengths() {
+    let expected = [
+        "0", "100", "101", "11000", "11001", "11010", "11011", "1110000",
+    ];
+    for (v, code) in expected.iter().enumerate() {
+        assert_eq!(encode_unbounded(v as u32), *code);
+    }
+    for v in 0..5000u32 {
+        assert_eq!(encode_unbounded(v).len(), 2 * (v + 1).ilog2() as usize + 1);
+    }
+}
+
+#[test]
+fn test_prefix_free() {
+    let codes: Vec<BbsePath> = (0..300u16).map(encode_unbounded).collect();
+    for (a, ca) in codes.iter().enumerate() {
+        for (b, cb) in codes.iter().enumerate() {
+            if a != b {
+                assert!(!cb.starts_with(ca), "{} prefixes {}", ca, cb);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_type_maximum() {
+    // The last bucket of u8 is [127, 254] and 255 sits alone after it.
+    assert_eq!(encode_unbounded(u8::MAX), "11111111");
+    assert_eq!(encode_unbounded(254u8).len(), 15);
+    for v in [0, 1, u128::MAX / 2, u128::MAX - 1, u128::MAX] {
+        assert_eq!(try_decode_unbounded(&encode_unbounded(v)), Ok(v));
+    }
+    assert_eq!(
+        try_decode_unbounded(&encode_unbounded(i64::MAX)),
+        Ok(i64::MAX)
+    );
+}
+
+#[test]
+fn test_signed() {
+    for v in 0..200i16 {
+        assert_eq!(encode_unbounded(v), encode_unbounded(v as u16));
+    }
+    assert_eq!(
+        try_encode_unbounded(-1i16),
+        Err(BbseError::TargetOutOfRange {
+            target: (-1i16).into(),
+            first: 0i16.into(),
+            last: i16::MAX.into(),
+        })
+    );
+}
+
+#[test]
+fn test_decode_errors() {
+    let path: BbsePath = "1101".parse().unwrap();
+    assert_eq!(
+        try_decode_unbounded::<u32>(&path),
+        Err(BbseError::UnexpectedEnd { position: 0 })
+    );
+    let path: BbsePath = "1000".parse().unwrap();
+    assert_eq!(
+        try_decode_unbounded::<u32>(&path),
+        Err(BbseError::PathTooLong { len: 4, max: 3 })
+    );
+    assert_eq!(decode_unbounded::<u32>(&path), 1);
+}
+
+#[test]
+fn test_stream() {
+    let mut writer = BbseWriter::new();
+    for run in [0u64, 7, 1, 300, 0, 65_000] {
+        writer.write_unbounded(run).unwrap();
+        writer.write(0u8, 4, 3).unwrap();
+    }
+    assert!(writer.write_unbounded(-3i32).is_err());
+    let path = writer.into_path();
+
+    let mut reader = BbseReader::new(&path);
+    for run in [0u64, 7, 1, 300, 0, 65_000] {
+        assert_eq!(reader.read_unbounded(), Ok(run));
+        assert_eq!(reader.read(0u8, 4), Ok(3));
+    }
+    assert!(reader.is_empty());
+    assert_eq!(
+        reader.read_unbounded::<u64>(),
+        Err(BbseError::UnexpectedEnd {
+            position: path.len()
+        })
+    );
+}
+
+proptest! {
+    #[test]
+    fn roundtrip(v in any::<u64>()) {
+        let path = encode_unbounded(v);
+        prop_assert!(path.len() <= 2 * 64 + 1);
+        prop_assert_eq!(try_decode_unbounded(&path), Ok(v));
+    }
+}
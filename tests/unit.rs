@@ -1,4 +1,9 @@
-use bbse::{decode, decode_from, encode, encode_from, BBSEStack};
+#![cfg(feature = "alloc")]
+
+use bbse::{
+    decode, decode_from, encode, encode_from, try_decode, try_decode_from, try_encode,
+    try_encode_from, validate_path, validate_path_from, BBSEStack, BbseError, BbsePath,
+};
 
 #[test]
 fn test_basic_encode_decode() {
@@ -115,3 +120,185 @@ fn test_custom_midpoint_center_precision() {
         );
     }
 }
+
+#[test]
+fn test_try_encode_matches_encode() {
+    for target in 0..100 {
+        assert_eq!(try_encode(0, 100, target), Ok(encode(0, 100, target)));
+        assert_eq!(
+            try_encode_from(0, 100, target, 10),
+            Ok(encode_from(0, 100, target, 10))
+        );
+    }
+}
+
+#[test]
+fn test_try_encode_errors() {
+    assert_eq!(
+        try_encode(10, 10, 10),
+        Err(BbseError::EmptyRange {
+            start: 10.into(),
+            end: 10.into()
+        })
+    );
+    assert_eq!(
+        try_encode(0, 5, 5),
+        Err(BbseError::TargetOutOfBounds {
+            target: 5.into(),
+            start: 0.into(),
+            end: 5.into()
+        })
+    );
+    assert_eq!(
+        try_encode_from(0, 10, 5, 0),
+        Err(BbseError::InvalidMidpoint {
+            midpoint: 0.into(),
+            start: 0.into(),
+            end: 10.into()
+        })
+    );
+    assert_eq!(
+        try_encode_from(0, 10, 5, 10).unwrap_err().to_string(),
+        "midpoint (10) must be within (start=0, end=10)"
+    );
+}
+
+#[test]
+fn test_try_decode_roundtrip() {
+    for target in 0..256 {
+        let path = encode(0, 256, target);
+        assert_eq!(try_decode(0, 256, &path), Ok(target));
+
+        let path = encode_from(0, 256, target, 200);
+        assert_eq!(try_decode_from(0, 256, &path, 200), Ok(target));
+    }
+}
+
+#[test]
+fn test_try_decode_errors() {
+    let mut path = encode(0, 8, 0);
+    assert_eq!(path.len(), 3);
+    path.push(false);
+    assert_eq!(
+        try_decode(0, 8, &path),
+        Err(BbseError::PathTooLong { len: 4, max: 3 })
+    );
+    assert_eq!(
+        try_decode_from(0, 8, &path, 4),
+        Err(BbseError::PathTooLong { len: 4, max: 3 })
+    );
+    assert_eq!(
+        try_decode(3, 3, &path),
+        Err(BbseError::EmptyRange {
+            start: 3.into(),
+            end: 3.into()
+        })
+    );
+    assert_eq!(
+        try_decode_from(0, 8, &path, 8),
+        Err(BbseError::InvalidMidpoint {
+            midpoint: 8.into(),
+            start: 0.into(),
+            end: 8.into()
+        })
+    );
+}
+
+fn all_paths(max_len: usize) -> Vec<BbsePath> {
+    let mut paths = vec![BbsePath::new()];
+    for len in 1..=max_len {
+        for bits in 0..(1u32 << len) {
+            paths.push((0..len).rev().map(|i| bits >> i & 1 == 1).collect());
+        }
+    }
+    paths
+}
+
+#[test]
+fn test_try_decode_accepts_exactly_encoded_paths() {
+    for end in 1..20 {
+        for path in all_paths(6) {
+            let canonical = match try_decode(0, end, &path) {
+                Ok(value) => {
+                    assert_eq!(encode(0, end, value), path, "end={} path={}", end, path);
+                    true
+                }
+                Err(_) => false,
+            };
+            let encoded = (0..end).any(|v| encode(0, end, v) == path);
+            assert_eq!(canonical, encoded, "end={} path={}", end, path);
+        }
+    }
+}
+
+#[test]
+fn test_try_decode_from_accepts_exactly_encoded_paths() {
+    for end in 2..20 {
+        for midpoint in 1..end {
+            for path in all_paths(6) {
+                let canonical = match try_decode_from(0, end, &path, midpoint) {
+                    Ok(value) => {
+                        assert_eq!(encode_from(0, end, value, midpoint), path);
+                        true
+                    }
+                    Err(_) => false,
+                };
+                let encoded = (0..end).any(|v| encode_from(0, end, v, midpoint) == path);
+                assert_eq!(
+                    canonical, encoded,
+                    "end={} mid={} path={}",
+                    end, midpoint, path
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_validate_path_reports_position() {
+    // [0, 2): "1" re-enters the midpoint 1, which `encode` stops on.
+    assert_eq!(
+        validate_path(0, 2, &path("1")),
+        Err(BbseError::UnreachableValue { position: 0 })
+    );
+    // [0, 8): "100" narrows [4, 8) to [4, 6) and then to the visited 4.
+    assert_eq!(
+        validate_path(0, 8, &path("100")),
+        Err(BbseError::UnreachableValue { position: 2 })
+    );
+    assert_eq!(
+        validate_path_from(0, 8, &path("0001"), 3),
+        Err(BbseError::PathTooLong { len: 4, max: 2 })
+    );
+    assert_eq!(validate_path(0, 8, &path("10")), Ok(()));
+}
+
+fn path(bits: &str) -> BbsePath {
+    bits.parse().unwrap()
+}
+
+#[test]
+fn test_path_helpers() {
+    let p = encode(0, 256, 77);
+    assert_eq!(format!("{:?}", p), "BbsePath(\"0100110\")");
+    assert_eq!(p.len(), 7);
+    assert_eq!(p.to_string().parse::<BbsePath>(), Ok(p.clone()));
+    assert_eq!(p, "0100110");
+    assert_eq!(p.iter().collect::<BbsePath>(), p);
+    assert_eq!(p.get(1), Some(true));
+    assert_eq!(p.get(2), Some(false));
+    assert_eq!(p.get(7), None);
+    assert!(p.starts_with(&path("0100")));
+    assert!(!p.starts_with(&path("1")));
+    assert_eq!(p.as_raw_slice(), &[0b0100_1100]);
+    assert_eq!(
+        BbsePath::from_raw_parts(p.as_raw_slice(), 7),
+        Some(p.clone())
+    );
+    assert_eq!(BbsePath::from_raw_parts(&[0xff], 9), None);
+    assert!(path("0") < path("1"));
+    assert!("012".parse::<BbsePath>().is_err());
+
+    let bits: bitvec::vec::BitVec<u8, bitvec::order::Msb0> = p.clone().into();
+    assert_eq!(BbsePath::from(bits), p);
+}